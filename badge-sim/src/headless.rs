@@ -0,0 +1,107 @@
+//! Running a script without a terminal, saving what the panel shows after each refresh.
+
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use super::script::{Condition, Line, Step};
+use super::{Refresh, Simulator, HEIGHT, WIDTH};
+
+/// A refresh and the PNG it was saved to.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Frame {
+    /// Position among the refreshes, from 0 for the one made at startup.
+    pub number: usize,
+    pub refresh: Refresh,
+    pub path: PathBuf,
+}
+
+/// Runs `script`, saving the panel as `NNN-KIND.png` in `dir` after the startup refresh and
+/// every refresh after it. Each pixel is drawn `scale` times across and down.
+pub fn run(
+    simulator: &mut Simulator,
+    script: &[Line],
+    dir: &Path,
+    scale: usize,
+) -> Result<Vec<Frame>, String> {
+    fs::create_dir_all(dir).map_err(|error| format!("{}: {}", dir.display(), error))?;
+    let mut frames = Vec::new();
+    if let Some(refresh) = simulator.last_refresh() {
+        frames.push(save(simulator, refresh, frames.len(), dir, scale)?);
+    }
+
+    let mut last = None;
+    for line in script {
+        let failed = |what: String| Err(format!("line {}: {}", line.number, what));
+        match line.step {
+            Step::Press(button) => {
+                last = simulator.press(button);
+                if let Some(refresh) = last {
+                    frames.push(save(simulator, refresh, frames.len(), dir, scale)?);
+                }
+            }
+            Step::Wait { ms } => simulator.wait(ms),
+            Step::Expect(Condition::Screen(screen)) => {
+                let actual = simulator.app().screen();
+                if actual != screen {
+                    return failed(format!("expected {:?}, but showing {:?}", screen, actual));
+                }
+            }
+            Step::Expect(Condition::Refresh(kind)) => {
+                let actual = last.map(|refresh| refresh.kind);
+                if actual != kind {
+                    return failed(format!("expected {:?} refresh, but got {:?}", kind, actual));
+                }
+            }
+        }
+    }
+    Ok(frames)
+}
+
+fn save(
+    simulator: &Simulator,
+    refresh: Refresh,
+    number: usize,
+    dir: &Path,
+    scale: usize,
+) -> Result<Frame, String> {
+    let kind = format!("{:?}", refresh.kind).to_lowercase();
+    let path = dir.join(format!("{:03}-{}.png", number, kind));
+    write_png(simulator, refresh, &path, scale)
+        .map_err(|error| format!("{}: {}", path.display(), error))?;
+    Ok(Frame {
+        number,
+        refresh,
+        path,
+    })
+}
+
+/// Writes the panel as 8-bit grayscale, with the refresh in text chunks.
+fn write_png(
+    simulator: &Simulator,
+    refresh: Refresh,
+    path: &Path,
+    scale: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (width, height) = (WIDTH as usize * scale, HEIGHT as usize * scale);
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            pixels.push(255 - simulator.darkness(x / scale, y / scale));
+        }
+    }
+
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(File::create(path)?),
+        width as u32,
+        height as u32,
+    );
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.add_text_chunk("Refresh".into(), format!("{:?}", refresh.kind))?;
+    encoder.add_text_chunk("Start".into(), format!("{} ms", refresh.start_ms))?;
+    encoder.add_text_chunk("Duration".into(), format!("{} ms", refresh.duration_ms))?;
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixels)?;
+    Ok(())
+}
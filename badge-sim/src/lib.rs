@@ -0,0 +1,171 @@
+//! The badge application from `badge-ui` running against the emulated panel, wired up the way
+//! the firmware does it, so screens can be tried out without flashing the badge.
+//!
+//! Time is simulated: it only passes while the panel refreshes, for as long as the emulator works
+//! out the refresh would keep BUSY low, and when a [script](script) waits.
+
+pub mod headless;
+pub mod script;
+
+use badge_ui::{App, Button, NameBadge};
+use uc8151::framebuffer::Framebuffer;
+use uc8151::profile::{self, PanelProfile};
+use uc8151::refresh_policy::RefreshKind;
+use uc8151::rotation::Rotation;
+use uc8151_emulator::{Display, Emulator, Pixel, Report};
+
+pub use badge_ui::{HEIGHT, WIDTH};
+
+pub const PANEL: PanelProfile = profile::PANEL_2IN9_128X296;
+
+/// The badge is held with the panel in landscape.
+pub const ROTATION: Rotation = Rotation::Deg90;
+
+type Frame = Framebuffer<16, 296>;
+
+/// An update of the panel, which may take several refreshes for several regions.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Refresh {
+    pub kind: RefreshKind,
+    /// Simulated time at which it started, counted from when the badge started up.
+    pub start_ms: u32,
+    pub duration_ms: u32,
+}
+
+pub struct Simulator<'a> {
+    emulator: Emulator,
+    display: Display,
+    frame: Frame,
+    app: App<'a>,
+    now_ms: u32,
+    last: Option<Refresh>,
+}
+
+impl<'a> Simulator<'a> {
+    /// Starts the badge up showing `badge`.
+    pub fn new(badge: NameBadge<'a>) -> Self {
+        let emulator = Emulator::new();
+        let mut display = emulator.display(PANEL);
+        display.setup();
+        display.set_rotation(ROTATION);
+        let mut frame = Frame::new();
+        frame.set_rotation(ROTATION);
+
+        let app = App::new(badge);
+        app.draw(&mut frame).unwrap();
+        let mut simulator = Self {
+            emulator,
+            display,
+            frame,
+            app,
+            now_ms: 0,
+            last: None,
+        };
+        simulator.flush();
+        simulator
+    }
+
+    pub fn app(&self) -> &App<'a> {
+        &self.app
+    }
+
+    /// Simulated time since the badge started up.
+    pub fn now_ms(&self) -> u32 {
+        self.now_ms
+    }
+
+    /// The last update of the panel, including the one made at startup.
+    pub fn last_refresh(&self) -> Option<Refresh> {
+        self.last
+    }
+
+    /// Presses `button` and refreshes whatever it changed, which takes as long as the
+    /// refreshes do. Returns `None` if nothing changed.
+    pub fn press(&mut self, button: Button) -> Option<Refresh> {
+        self.app.press(button, &mut self.frame).unwrap();
+        self.flush()
+    }
+
+    /// Lets `ms` pass with nothing happening.
+    pub fn wait(&mut self, ms: u32) {
+        self.now_ms += ms;
+    }
+
+    fn flush(&mut self) -> Option<Refresh> {
+        let before_us = self.busy_us();
+        let kind = self.frame.flush(&mut self.display).unwrap()?;
+        let refresh = Refresh {
+            kind,
+            start_ms: self.now_ms,
+            duration_ms: ((self.busy_us() - before_us + 500) / 1000) as u32,
+        };
+        self.now_ms += refresh.duration_ms;
+        self.last = Some(refresh);
+        Some(refresh)
+    }
+
+    /// How dark the panel is at `x`, `y`, the way up the badge is held, from 0 for white to
+    /// 255 for black. Red shows as mid gray.
+    pub fn darkness(&self, x: usize, y: usize) -> u8 {
+        let (x, y) = ROTATION.to_panel(x, y, PANEL.width as usize);
+        match self.emulator.controller().pixel(x, y) {
+            Pixel::Gray(darkness) => darkness,
+            Pixel::Red => 128,
+        }
+    }
+
+    pub fn refreshes(&self) -> u32 {
+        self.emulator.controller().refreshes()
+    }
+
+    fn busy_us(&self) -> u64 {
+        self.emulator.controller().busy_us()
+    }
+
+    /// Anything the driver has sent that the controller wouldn't have accepted.
+    pub fn violations(&self) -> Vec<Report> {
+        self.emulator.controller().violations()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use badge_ui::Screen;
+
+    #[test]
+    fn test_buttons_refresh_the_panel() {
+        let mut simulator = Simulator::new(NameBadge {
+            heading: "HEADING",
+            name: "Name",
+            detail: "detail",
+        });
+        assert_eq!(1, simulator.refreshes());
+        // The header bar is black, under it is white.
+        assert_eq!(255, simulator.darkness(0, 0));
+        assert_eq!(0, simulator.darkness(0, HEIGHT as usize - 1));
+
+        assert_eq!(None, simulator.press(Button::C));
+        let kind = |refresh: Option<Refresh>| refresh.map(|refresh| refresh.kind);
+        assert_eq!(Some(RefreshKind::Full), kind(simulator.press(Button::User)));
+        assert_eq!(Screen::Menu, simulator.app().screen());
+        assert_eq!(
+            Some(RefreshKind::Partial),
+            kind(simulator.press(Button::Down))
+        );
+        assert_eq!(3, simulator.refreshes());
+
+        // Every refresh so far has played the default LUTs, 1630 frames at 100Hz, through.
+        assert_eq!(
+            Some(Refresh {
+                kind: RefreshKind::Partial,
+                start_ms: 3 * 16_300,
+                duration_ms: 16_300
+            }),
+            simulator.press(Button::Up)
+        );
+        simulator.wait(250);
+        assert_eq!(4 * 16_300 + 250, simulator.now_ms());
+        assert!(simulator.violations().is_empty());
+    }
+}
@@ -0,0 +1,274 @@
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use badge_sim::{headless, script, Simulator, HEIGHT, WIDTH};
+use badge_ui::{Button, NameBadge};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+const USAGE: &str = "\
+usage: badge-sim [--realtime] [NAME [DETAIL]]
+       badge-sim --script SCRIPT [--out DIR] [--scale N] [NAME [DETAIL]]
+
+Runs the badge in the terminal, showing the panel two pixels to a character, so the terminal
+needs to be at least 296 columns wide to show all of it.
+
+  a, b, c     the A, B and C buttons
+  up, down    the UP and DOWN buttons
+  u, space    the USER button
+  q, escape   quit
+
+With --realtime, each refresh takes as long as it would on the badge, and buttons pressed
+meanwhile are ignored, as the badge doesn't look at them until the panel is done.
+
+With --script, runs the button presses in SCRIPT instead, and saves what the panel shows after
+each refresh as a PNG in DIR (frames by default), each pixel drawn N times across and down. See
+the script module for the format. Fails if the script's expectations aren't met or the driver
+sends anything the controller wouldn't accept.";
+
+const KEYS: &str = "a/b/c: A/B/C  up/down: UP/DOWN  u: USER  q: quit";
+
+/// Puts the terminal back however the simulator exits.
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), ResetColor, Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn button(key: KeyEvent) -> Option<Button> {
+    Some(match key.code {
+        KeyCode::Char('a') => Button::A,
+        KeyCode::Char('b') => Button::B,
+        KeyCode::Char('c') => Button::C,
+        KeyCode::Up => Button::Up,
+        KeyCode::Down => Button::Down,
+        KeyCode::Char('u') | KeyCode::Char(' ') => Button::User,
+        _ => return None,
+    })
+}
+
+fn is_quit(key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => true,
+        KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+        _ => false,
+    }
+}
+
+fn colour(darkness: u8) -> Color {
+    let level = 255 - darkness;
+    Color::Rgb {
+        r: level,
+        g: level,
+        b: level,
+    }
+}
+
+/// Draws the panel with upper half blocks, the top pixel in the foreground colour and the one
+/// below it in the background colour, followed by a status line.
+fn render(out: &mut impl Write, simulator: &Simulator, status: &str) -> io::Result<()> {
+    let (columns, _) = terminal::size()?;
+    let width = (WIDTH as usize).min(columns as usize);
+
+    queue!(out, Clear(ClearType::All))?;
+    for row in 0..HEIGHT as usize / 2 {
+        queue!(out, MoveTo(0, row as u16))?;
+        // Most of the panel is one colour, so only change colours when they do.
+        let mut colours = None;
+        for x in 0..width {
+            let pair = (
+                simulator.darkness(x, row * 2),
+                simulator.darkness(x, row * 2 + 1),
+            );
+            if colours != Some(pair) {
+                queue!(
+                    out,
+                    SetForegroundColor(colour(pair.0)),
+                    SetBackgroundColor(colour(pair.1))
+                )?;
+                colours = Some(pair);
+            }
+            queue!(out, Print('▀'))?;
+        }
+    }
+    queue!(
+        out,
+        ResetColor,
+        MoveTo(0, HEIGHT as u16 / 2 + 1),
+        Print(KEYS)
+    )?;
+    render_status(out, status)
+}
+
+fn render_status(out: &mut impl Write, status: &str) -> io::Result<()> {
+    queue!(
+        out,
+        MoveTo(0, HEIGHT as u16 / 2 + 2),
+        Clear(ClearType::CurrentLine),
+        Print(status)
+    )?;
+    out.flush()
+}
+
+fn status(simulator: &Simulator) -> String {
+    let last = match simulator.last_refresh() {
+        Some(refresh) => format!("{:?}, {} ms", refresh.kind, refresh.duration_ms),
+        None => "none".into(),
+    };
+    let mut status = format!(
+        "screen: {:?}  refreshes: {}  last: {}  time: {} ms",
+        simulator.app().screen(),
+        simulator.refreshes(),
+        last,
+        simulator.now_ms()
+    );
+    let violations = simulator.violations().len();
+    if violations > 0 {
+        status += &format!("  violations: {}", violations);
+    }
+    status
+}
+
+fn run_terminal(badge: NameBadge, options: &Options) -> Result<(), Box<dyn Error>> {
+    let mut simulator = Simulator::new(badge);
+
+    let _terminal = RawTerminal::enter()?;
+    let mut out = io::stdout();
+    render(&mut out, &simulator, &status(&simulator))?;
+    loop {
+        match event::read()? {
+            Event::Key(key) if key.kind != KeyEventKind::Press => continue,
+            Event::Key(key) if is_quit(key) => return Ok(()),
+            Event::Key(key) => match button(key) {
+                Some(button) => match simulator.press(button) {
+                    // The panel keeps showing the old screen until the refresh is done.
+                    Some(refresh) if options.realtime => {
+                        let busy = format!("refreshing: {:?}", refresh.kind);
+                        render_status(&mut out, &busy)?;
+                        thread::sleep(Duration::from_millis(refresh.duration_ms.into()));
+                        while event::poll(Duration::ZERO)? {
+                            event::read()?;
+                        }
+                    }
+                    _ => {}
+                },
+                None => continue,
+            },
+            Event::Resize(..) => {}
+            _ => continue,
+        }
+        render(&mut out, &simulator, &status(&simulator))?;
+    }
+}
+
+fn run_headless(badge: NameBadge, options: &Options) -> Result<(), Box<dyn Error>> {
+    let path = options.script.as_deref().unwrap_or_default();
+    let script = script::parse(&fs::read_to_string(path)?)?;
+    let mut simulator = Simulator::new(badge);
+    let frames = headless::run(
+        &mut simulator,
+        &script,
+        Path::new(&options.out),
+        options.scale,
+    )?;
+
+    for frame in frames {
+        println!(
+            "{:>8} ms  {:<8} {:>6} ms  {}",
+            frame.refresh.start_ms,
+            format!("{:?}", frame.refresh.kind),
+            frame.refresh.duration_ms,
+            frame.path.display()
+        );
+    }
+    let violations = simulator.violations();
+    if !violations.is_empty() {
+        return Err(format!("the controller would not have accepted: {:?}", violations).into());
+    }
+    Ok(())
+}
+
+struct Options {
+    script: Option<String>,
+    out: String,
+    scale: usize,
+    realtime: bool,
+    name: Option<String>,
+    detail: Option<String>,
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        script: None,
+        out: "frames".into(),
+        scale: 1,
+        realtime: false,
+        name: None,
+        detail: None,
+    };
+    let mut names = Vec::new();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--script" => options.script = Some(value()?),
+            "--out" => options.out = value()?,
+            "--realtime" => options.realtime = true,
+            "--scale" => match value()?.parse() {
+                Ok(scale) if scale > 0 => options.scale = scale,
+                _ => return Err("--scale needs a whole number from 1".into()),
+            },
+            _ if arg.starts_with('-') => return Err(format!("unknown option {}", arg)),
+            _ => names.push(arg),
+        }
+    }
+    if names.len() > 2 {
+        return Err("expected at most a NAME and a DETAIL".into());
+    }
+    let mut names = names.into_iter();
+    options.name = names.next();
+    options.detail = names.next();
+    Ok(options)
+}
+
+fn main() {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            process::exit(1);
+        }
+    };
+
+    let badge = NameBadge {
+        heading: "BADGER 2040",
+        name: options.name.as_deref().unwrap_or("Your Name"),
+        detail: options.detail.as_deref().unwrap_or("written in Rust"),
+    };
+    let result = match options.script {
+        Some(_) => run_headless(badge, &options),
+        None => run_terminal(badge, &options),
+    };
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
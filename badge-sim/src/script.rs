@@ -0,0 +1,145 @@
+//! Scripts of button presses for running the badge without a terminal.
+//!
+//! One step per line, with anything after a `#` ignored:
+//!
+//! ```text
+//! press user              # a, b, c, up, down or user
+//! wait 2s                 # or e.g. 500ms
+//! expect screen menu      # name-badge, menu or about
+//! expect refresh partial  # full, partial, fast or none, for the last press
+//! ```
+//!
+//! The badge only changes when a button is pressed, and each press runs to the end of its
+//! refresh, so there is never anything to wait for. Conditions are checked instead, and the
+//! script fails at the first one that doesn't hold.
+
+use badge_ui::{Button, Screen};
+use uc8151::refresh_policy::RefreshKind;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Condition {
+    Screen(Screen),
+    /// What the last press refreshed, if anything.
+    Refresh(Option<RefreshKind>),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Step {
+    Press(Button),
+    Wait { ms: u32 },
+    Expect(Condition),
+}
+
+/// A step and the line it came from, counting from 1.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Line {
+    pub number: usize,
+    pub step: Step,
+}
+
+fn button(name: &str) -> Option<Button> {
+    Some(match name {
+        "a" => Button::A,
+        "b" => Button::B,
+        "c" => Button::C,
+        "up" => Button::Up,
+        "down" => Button::Down,
+        "user" => Button::User,
+        _ => return None,
+    })
+}
+
+fn screen(name: &str) -> Option<Screen> {
+    Some(match name {
+        "name-badge" => Screen::NameBadge,
+        "menu" => Screen::Menu,
+        "about" => Screen::About,
+        _ => return None,
+    })
+}
+
+fn refresh(name: &str) -> Option<Option<RefreshKind>> {
+    Some(match name {
+        "full" => Some(RefreshKind::Full),
+        "partial" => Some(RefreshKind::Partial),
+        "fast" => Some(RefreshKind::Fast),
+        "none" => None,
+        _ => return None,
+    })
+}
+
+fn duration_ms(duration: &str) -> Option<u32> {
+    match duration.strip_suffix("ms") {
+        Some(ms) => ms.parse().ok(),
+        None => duration
+            .strip_suffix('s')?
+            .parse::<u32>()
+            .ok()?
+            .checked_mul(1000),
+    }
+}
+
+fn step(words: &[&str]) -> Option<Step> {
+    Some(match *words {
+        ["press", name] => Step::Press(button(name)?),
+        ["wait", duration] => Step::Wait {
+            ms: duration_ms(duration)?,
+        },
+        ["expect", "screen", name] => Step::Expect(Condition::Screen(screen(name)?)),
+        ["expect", "refresh", name] => Step::Expect(Condition::Refresh(refresh(name)?)),
+        _ => return None,
+    })
+}
+
+pub fn parse(text: &str) -> Result<Vec<Line>, String> {
+    let mut lines = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").to_lowercase();
+        let words: Vec<_> = line.split_whitespace().collect();
+        if words.is_empty() {
+            continue;
+        }
+        let step = step(&words)
+            .ok_or_else(|| format!("line {}: can't make sense of {:?}", index + 1, line))?;
+        lines.push(Line {
+            number: index + 1,
+            step,
+        });
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let script = "\
+            # Open the menu\n\
+            press USER\n\
+            \n\
+            wait 1500ms  # then wait\n\
+            wait 2s\n\
+            expect screen name-badge\n\
+            expect refresh none\n";
+        let steps: Vec<_> = parse(script)
+            .unwrap()
+            .into_iter()
+            .map(|line| (line.number, line.step))
+            .collect();
+        assert_eq!(
+            vec![
+                (2, Step::Press(Button::User)),
+                (4, Step::Wait { ms: 1500 }),
+                (5, Step::Wait { ms: 2000 }),
+                (6, Step::Expect(Condition::Screen(Screen::NameBadge))),
+                (7, Step::Expect(Condition::Refresh(None))),
+            ],
+            steps
+        );
+
+        assert!(parse("press x").unwrap_err().starts_with("line 1:"));
+        assert!(parse("wait 5").is_err());
+    }
+}
@@ -0,0 +1,104 @@
+//! What the badge does when its buttons are pressed.
+//!
+//! The badge starts on the name badge. USER or A opens the menu, where UP and DOWN move the
+//! highlight, A opens the highlighted item and B goes back to the name badge.
+
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+
+use super::{Menu, NameBadge};
+
+/// The buttons along the bottom and side of the Badger 2040, wired as in the firmware's
+/// `pins.rs`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Button {
+    A,
+    B,
+    C,
+    Up,
+    Down,
+    User,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Screen {
+    NameBadge,
+    Menu,
+    About,
+}
+
+const MENU_ITEMS: [&str; 2] = ["Name badge", "About"];
+
+const ABOUT: NameBadge<'static> = NameBadge {
+    heading: "ABOUT",
+    name: "badge-rs",
+    detail: "Press any button to go back",
+};
+
+pub struct App<'a> {
+    badge: NameBadge<'a>,
+    menu: Menu<'static>,
+    screen: Screen,
+}
+
+impl<'a> App<'a> {
+    pub fn new(badge: NameBadge<'a>) -> Self {
+        Self {
+            badge,
+            menu: Menu {
+                title: "Menu",
+                items: &MENU_ITEMS,
+                selected: 0,
+            },
+            screen: Screen::NameBadge,
+        }
+    }
+
+    pub fn screen(&self) -> Screen {
+        self.screen
+    }
+
+    /// Draws the whole of the current screen.
+    pub fn draw<D: DrawTarget<Color = BinaryColor>>(&self, target: &mut D) -> Result<(), D::Error> {
+        match self.screen {
+            Screen::NameBadge => self.badge.draw(target),
+            Screen::Menu => self.menu.draw(target),
+            Screen::About => ABOUT.draw(target),
+        }
+    }
+
+    /// Acts on `button`, redrawing whatever it changes. Returns `false` if the button does
+    /// nothing on this screen.
+    pub fn press<D: DrawTarget<Color = BinaryColor>>(
+        &mut self,
+        button: Button,
+        target: &mut D,
+    ) -> Result<bool, D::Error> {
+        let selected = self.menu.selected;
+        let screen = match (self.screen, button) {
+            (Screen::NameBadge, Button::User) | (Screen::NameBadge, Button::A) => Screen::Menu,
+            (Screen::NameBadge, _) => return Ok(false),
+
+            (Screen::Menu, Button::Up) if selected > 0 => {
+                self.menu.select(target, selected - 1)?;
+                return Ok(true);
+            }
+            (Screen::Menu, Button::Down) if selected + 1 < MENU_ITEMS.len() => {
+                self.menu.select(target, selected + 1)?;
+                return Ok(true);
+            }
+            (Screen::Menu, Button::A) => match selected {
+                0 => Screen::NameBadge,
+                _ => Screen::About,
+            },
+            (Screen::Menu, Button::B) => Screen::NameBadge,
+            (Screen::Menu, _) => return Ok(false),
+
+            (Screen::About, _) => Screen::Menu,
+        };
+
+        self.screen = screen;
+        self.draw(target)?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,28 @@
+//! The badge's screens.
+//!
+//! Everything is drawn in `BinaryColor`, where `On` is black, onto any target [`WIDTH`] by
+//! [`HEIGHT`] pixels, i.e. the panel held in landscape. The screens know nothing about the panel,
+//! so the firmware draws them into a framebuffer and the host tests render them through the
+//! emulator to compare against golden images.
+#![no_std]
+
+pub mod app;
+pub mod menu;
+pub mod name_badge;
+
+pub use app::{App, Button, Screen};
+pub use menu::Menu;
+pub use name_badge::NameBadge;
+
+use embedded_graphics::text::{Alignment, Baseline, TextStyle, TextStyleBuilder};
+
+pub const WIDTH: u32 = 296;
+pub const HEIGHT: u32 = 128;
+
+/// Text centred on its position both ways.
+fn centred() -> TextStyle {
+    TextStyleBuilder::new()
+        .alignment(Alignment::Center)
+        .baseline(Baseline::Middle)
+        .build()
+}
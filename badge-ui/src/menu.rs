@@ -0,0 +1,93 @@
+//! A list of options with one highlighted, moved with the buttons.
+
+use core::mem;
+
+use embedded_graphics::mono_font::ascii::FONT_10X20;
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+use embedded_graphics::text::{Baseline, Text};
+
+use super::WIDTH;
+
+const TITLE_HEIGHT: u32 = 24;
+const ROW_HEIGHT: u32 = 20;
+
+/// Room for five items; any more are cut off.
+pub struct Menu<'a> {
+    pub title: &'a str,
+    pub items: &'a [&'a str],
+    pub selected: usize,
+}
+
+impl Menu<'_> {
+    pub fn draw<D: DrawTarget<Color = BinaryColor>>(&self, target: &mut D) -> Result<(), D::Error> {
+        target.clear(BinaryColor::Off)?;
+
+        Text::with_baseline(
+            self.title,
+            Point::new(4, 1),
+            MonoTextStyle::new(&FONT_10X20, BinaryColor::On),
+            Baseline::Top,
+        )
+        .draw(target)?;
+        Rectangle::new(Point::new(0, TITLE_HEIGHT as i32 - 2), Size::new(WIDTH, 2))
+            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+            .draw(target)?;
+
+        for index in 0..self.items.len() {
+            self.draw_item(target, index)?;
+        }
+        Ok(())
+    }
+
+    /// Where item `index` is drawn.
+    pub fn item_area(&self, index: usize) -> Rectangle {
+        Rectangle::new(
+            Point::new(0, (TITLE_HEIGHT + index as u32 * ROW_HEIGHT) as i32),
+            Size::new(WIDTH, ROW_HEIGHT),
+        )
+    }
+
+    /// Moves the highlight to item `index`. Only the two items that changed are redrawn, so a
+    /// partial refresh of them is enough to show it.
+    ///
+    /// Panics if there is no item `index`.
+    pub fn select<D: DrawTarget<Color = BinaryColor>>(
+        &mut self,
+        target: &mut D,
+        index: usize,
+    ) -> Result<(), D::Error> {
+        let previous = mem::replace(&mut self.selected, index);
+        if previous != index {
+            self.draw_item(target, previous)?;
+            self.draw_item(target, index)?;
+        }
+        Ok(())
+    }
+
+    fn draw_item<D: DrawTarget<Color = BinaryColor>>(
+        &self,
+        target: &mut D,
+        index: usize,
+    ) -> Result<(), D::Error> {
+        let (background, foreground) = match index == self.selected {
+            true => (BinaryColor::On, BinaryColor::Off),
+            false => (BinaryColor::Off, BinaryColor::On),
+        };
+        let area = self.item_area(index);
+        // Descenders would otherwise reach into the next item, which `select` doesn't redraw.
+        let mut target = target.clipped(&area);
+        area.into_styled(PrimitiveStyle::with_fill(background))
+            .draw(&mut target)?;
+        Text::with_baseline(
+            self.items[index],
+            area.top_left + Point::new(8, ROW_HEIGHT as i32 / 2),
+            MonoTextStyle::new(&FONT_10X20, foreground),
+            Baseline::Middle,
+        )
+        .draw(&mut target)?;
+        Ok(())
+    }
+}
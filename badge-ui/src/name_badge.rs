@@ -0,0 +1,54 @@
+//! The name badge, shown whenever nothing else is.
+
+use embedded_graphics::mono_font::ascii::{FONT_10X20, FONT_6X10};
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+use embedded_graphics::text::Text;
+
+use super::{centred, WIDTH};
+
+const HEADER_HEIGHT: u32 = 30;
+
+pub struct NameBadge<'a> {
+    /// Shown white on black across the top, e.g. the event.
+    pub heading: &'a str,
+    pub name: &'a str,
+    /// A smaller line under the name, e.g. pronouns or a handle.
+    pub detail: &'a str,
+}
+
+impl NameBadge<'_> {
+    pub fn draw<D: DrawTarget<Color = BinaryColor>>(&self, target: &mut D) -> Result<(), D::Error> {
+        let centre = WIDTH as i32 / 2;
+        target.clear(BinaryColor::Off)?;
+
+        Rectangle::new(Point::zero(), Size::new(WIDTH, HEADER_HEIGHT))
+            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+            .draw(target)?;
+        Text::with_text_style(
+            self.heading,
+            Point::new(centre, HEADER_HEIGHT as i32 / 2),
+            MonoTextStyle::new(&FONT_10X20, BinaryColor::Off),
+            centred(),
+        )
+        .draw(target)?;
+
+        Text::with_text_style(
+            self.name,
+            Point::new(centre, 70),
+            MonoTextStyle::new(&FONT_10X20, BinaryColor::On),
+            centred(),
+        )
+        .draw(target)?;
+        Text::with_text_style(
+            self.detail,
+            Point::new(centre, 104),
+            MonoTextStyle::new(&FONT_6X10, BinaryColor::On),
+            centred(),
+        )
+        .draw(target)?;
+        Ok(())
+    }
+}
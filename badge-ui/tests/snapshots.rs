@@ -0,0 +1,154 @@
+//! Renders the screens through the driver and emulator and checks what ends up on the panel
+//! against the golden images in `tests/snapshots`. See `host_tools::snapshot` for updating them.
+
+use std::path::PathBuf;
+
+use badge_ui::{App, Button, Menu, NameBadge, Screen, HEIGHT, WIDTH};
+use host_tools::pnm::GrayImage;
+use host_tools::snapshot::{assert_snapshot, diff};
+use uc8151::framebuffer::{Colour, Framebuffer};
+use uc8151::profile;
+use uc8151::refresh_policy::RefreshKind;
+use uc8151::rotation::Rotation;
+use uc8151_emulator::{Display, Emulator, Pixel, Report};
+
+type Frame = Framebuffer<16, 296>;
+
+/// The badge is held with the panel in landscape.
+const ROTATION: Rotation = Rotation::Deg90;
+
+const MENU_ITEMS: [&str; 4] = ["Name badge", "Image", "Clock", "Settings"];
+
+fn set_up() -> (Emulator, Display, Frame) {
+    let emulator = Emulator::new();
+    let mut display = emulator.display(profile::PANEL_2IN9_128X296);
+    display.setup();
+    display.set_rotation(ROTATION);
+    let mut frame = Frame::new();
+    frame.set_rotation(ROTATION);
+    (emulator, display, frame)
+}
+
+fn image(pixel: impl Fn(usize, usize) -> u8) -> GrayImage {
+    let (width, height) = (WIDTH as usize, HEIGHT as usize);
+    GrayImage {
+        width,
+        height,
+        pixels: (0..width * height)
+            .map(|index| pixel(index % width, index / width))
+            .collect(),
+    }
+}
+
+/// What the panel shows, the right way up.
+fn panel(emulator: &Emulator) -> GrayImage {
+    let controller = emulator.controller();
+    image(|x, y| {
+        let (x, y) = ROTATION.to_panel(x, y, 128);
+        match controller.pixel(x, y) {
+            Pixel::Gray(darkness) => 255 - darkness,
+            Pixel::Red => 128,
+        }
+    })
+}
+
+fn framebuffer(frame: &Frame) -> GrayImage {
+    image(|x, y| match frame.get_pixel(x, y) {
+        Some(Colour::Black) => 0,
+        _ => 255,
+    })
+}
+
+fn check(emulator: &Emulator, name: &str) {
+    assert_eq!(Vec::<Report>::new(), emulator.controller().violations());
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots");
+    assert_snapshot(&dir, name, &panel(emulator));
+}
+
+#[test]
+fn name_badge() {
+    let (emulator, mut display, mut frame) = set_up();
+    let badge = NameBadge {
+        heading: "RUST EMBEDDED",
+        name: "Ferris the Crab",
+        detail: "they/them - @ferris",
+    };
+    badge.draw(&mut frame).unwrap();
+
+    assert_eq!(Some(RefreshKind::Full), frame.flush(&mut display).unwrap());
+    check(&emulator, "name_badge");
+}
+
+#[test]
+fn menu() {
+    let (emulator, mut display, mut frame) = set_up();
+    let menu = Menu {
+        title: "Menu",
+        items: &MENU_ITEMS,
+        selected: 1,
+    };
+    menu.draw(&mut frame).unwrap();
+
+    assert_eq!(Some(RefreshKind::Full), frame.flush(&mut display).unwrap());
+    check(&emulator, "menu");
+}
+
+#[test]
+fn menu_selection_is_a_partial_update() {
+    let (emulator, mut display, mut frame) = set_up();
+    let mut menu = Menu {
+        title: "Menu",
+        items: &MENU_ITEMS,
+        selected: 1,
+    };
+    menu.draw(&mut frame).unwrap();
+    frame.flush(&mut display).unwrap();
+
+    menu.select(&mut frame, 3).unwrap();
+    assert_eq!(
+        Some(RefreshKind::Partial),
+        frame.flush(&mut display).unwrap()
+    );
+    check(&emulator, "menu_select_partial");
+
+    // The partial refresh leaves the panel as if the menu had been drawn from scratch.
+    let mut fresh = Frame::new();
+    fresh.set_rotation(ROTATION);
+    menu.draw(&mut fresh).unwrap();
+    let differences = diff(&framebuffer(&fresh), &panel(&emulator)).map(|(count, _)| count);
+    assert_eq!(None, differences);
+}
+
+#[test]
+fn app_navigation() {
+    let (emulator, mut display, mut frame) = set_up();
+    let mut app = App::new(NameBadge {
+        heading: "RUST EMBEDDED",
+        name: "Ferris the Crab",
+        detail: "they/them - @ferris",
+    });
+    app.draw(&mut frame).unwrap();
+    frame.flush(&mut display).unwrap();
+
+    let mut press = |button| {
+        let changed = app.press(button, &mut frame).unwrap();
+        let refresh = frame.flush(&mut display).unwrap();
+        assert_eq!(changed, refresh.is_some());
+        (app.screen(), refresh)
+    };
+    assert_eq!((Screen::NameBadge, None), press(Button::Down));
+    assert_eq!((Screen::Menu, Some(RefreshKind::Full)), press(Button::User));
+    assert_eq!((Screen::Menu, None), press(Button::Up));
+    assert_eq!(
+        (Screen::Menu, Some(RefreshKind::Partial)),
+        press(Button::Down)
+    );
+    assert_eq!((Screen::About, Some(RefreshKind::Full)), press(Button::A));
+    check(&emulator, "about");
+    assert_eq!((Screen::Menu, Some(RefreshKind::Full)), press(Button::C));
+    assert_eq!(
+        (Screen::NameBadge, Some(RefreshKind::Full)),
+        press(Button::B)
+    );
+    check(&emulator, "name_badge");
+}
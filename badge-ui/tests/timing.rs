@@ -0,0 +1,41 @@
+//! Performance budgets for the screens, timed by the emulator's model of how long each refresh
+//! keeps the panel busy.
+
+use badge_ui::{App, Button, NameBadge, Screen};
+use uc8151::framebuffer::Framebuffer;
+use uc8151::profile;
+use uc8151::refresh_policy::RefreshKind;
+use uc8151::rotation::Rotation;
+use uc8151_emulator::Emulator;
+
+#[test]
+fn menu_redraw_with_turbo_luts_is_under_400ms() {
+    let emulator = Emulator::new();
+    let mut display = emulator.display(profile::PANEL_2IN9_128X296);
+    display.setup();
+    display.set_rotation(Rotation::Deg90);
+    display.turbo_luts();
+    let mut frame = Framebuffer::<16, 296>::new();
+    frame.set_rotation(Rotation::Deg90);
+
+    let mut app = App::new(NameBadge {
+        heading: "BADGER 2040",
+        name: "Your Name",
+        detail: "written in Rust",
+    });
+    app.draw(&mut frame).unwrap();
+    frame.flush(&mut display).unwrap();
+    app.press(Button::User, &mut frame).unwrap();
+    frame.flush(&mut display).unwrap();
+    assert_eq!(Screen::Menu, app.screen());
+
+    let busy_us = || emulator.controller().busy_us();
+    let before = busy_us();
+    app.press(Button::Down, &mut frame).unwrap();
+    assert_eq!(
+        Some(RefreshKind::Partial),
+        frame.flush(&mut display).unwrap()
+    );
+    let redraw_us = busy_us() - before;
+    assert!(redraw_us < 400_000, "menu redraw took {} us", redraw_us);
+}
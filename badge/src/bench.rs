@@ -0,0 +1,60 @@
+//! Timing of the ways a frame can be sent to the controller.
+//!
+//! Enabled with the `bench-dtm` feature. Run it under a debugger with semihosting enabled to see
+//! the results, e.g. `monitor arm semihosting enable` in openocd.
+
+use crate::bsp::hal::Timer;
+use cortex_m_semihosting::hprintln;
+use embedded_hal::blocking::delay::DelayMs;
+use uc8151::interface::{Bus, Input, Output, Readback};
+use uc8151::trace::Trace;
+use uc8151::Uc8151;
+
+const ROUNDS: u64 = 10;
+
+/// Sends `frame` to DTM2 through the iterator and slice paths and prints how long each took.
+/// Nothing is refreshed, so the panel does not change.
+pub fn dtm_transfer<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>(
+    display: &mut Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>,
+    timer: &Timer,
+    frame: &[u8],
+) where
+    SPI: Bus,
+    DC: Output,
+    CS: Output,
+    BUSY: Input,
+    RESET: Output,
+    DELAY: DelayMs<u32>,
+    READBACK: Readback,
+    TRACE: Trace,
+{
+    let start = timer.get_counter();
+    for _ in 0..ROUNDS {
+        display.data_start_transmission_2(frame.iter().copied());
+        display.data_stop();
+    }
+    let iterator_us = (timer.get_counter() - start) / ROUNDS;
+
+    let start = timer.get_counter();
+    for _ in 0..ROUNDS {
+        display.data_start_transmission_2_slice(frame);
+        display.data_stop();
+    }
+    let slice_us = (timer.get_counter() - start) / ROUNDS;
+
+    hprintln!("DTM2, {} bytes", frame.len());
+    hprintln!(
+        "  iterator: {} us ({} KiB/s)",
+        iterator_us,
+        kib_per_s(frame.len(), iterator_us)
+    );
+    hprintln!(
+        "  slice:    {} us ({} KiB/s)",
+        slice_us,
+        kib_per_s(frame.len(), slice_us)
+    );
+}
+
+fn kib_per_s(bytes: usize, us: u64) -> u64 {
+    bytes as u64 * 1_000_000 / 1024 / us.max(1)
+}
@@ -0,0 +1,63 @@
+//! Reading the buttons, on the GPIOs listed in `pins.rs`.
+
+use badge_ui::Button;
+use embedded_hal::digital::v2::InputPin;
+
+const BUTTONS: [Button; 6] = [
+    Button::A,
+    Button::B,
+    Button::C,
+    Button::Up,
+    Button::Down,
+    Button::User,
+];
+
+/// A, B, C, UP and DOWN pull their pins high when pressed, so want pull-downs. USER pulls its
+/// pin low, so wants a pull-up.
+pub struct Buttons<A, B, C, UP, DOWN, USER> {
+    a: A,
+    b: B,
+    c: C,
+    up: UP,
+    down: DOWN,
+    user: USER,
+    held: [bool; 6],
+}
+
+impl<A, B, C, UP, DOWN, USER> Buttons<A, B, C, UP, DOWN, USER>
+where
+    A: InputPin,
+    B: InputPin,
+    C: InputPin,
+    UP: InputPin,
+    DOWN: InputPin,
+    USER: InputPin,
+{
+    pub fn new(a: A, b: B, c: C, up: UP, down: DOWN, user: USER) -> Self {
+        Self {
+            a,
+            b,
+            c,
+            up,
+            down,
+            user,
+            held: [false; 6],
+        }
+    }
+
+    /// A button that has been pressed since the last poll, if any. There is no debouncing, as
+    /// every press is followed by a refresh far longer than any bounce.
+    pub fn poll(&mut self) -> Option<Button> {
+        let held = [
+            self.a.is_high().unwrap_or(false),
+            self.b.is_high().unwrap_or(false),
+            self.c.is_high().unwrap_or(false),
+            self.up.is_high().unwrap_or(false),
+            self.down.is_high().unwrap_or(false),
+            self.user.is_low().unwrap_or(false),
+        ];
+        let pressed = (0..BUTTONS.len()).find(|&index| held[index] && !self.held[index]);
+        self.held = held;
+        pressed.map(|index| BUTTONS[index])
+    }
+}
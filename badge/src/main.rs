@@ -3,7 +3,6 @@
 //! This will blink an LED attached to GP25, which is the pin the Pico uses for the on-board LED.
 #![no_std]
 #![no_main]
-#![feature(slice_flatten)]
 
 use cortex_m_rt::entry;
 use cortex_m_semihosting::*;
@@ -34,12 +33,31 @@ use bsp::hal::{
 use usb_device::{class_prelude::*, prelude::*};
 use usbd_serial::SerialPort;
 
-mod uc8151;
-use crate::uc8151::Uc8151;
+#[cfg(feature = "bench-dtm")]
+mod bench;
+mod buttons;
+mod readback;
+mod scratch;
+#[cfg(feature = "trace")]
+mod trace;
+use uc8151::Uc8151;
 
+use badge_ui::{App, NameBadge};
 use numtoa::NumToA;
 
-use self::uc8151::register::*;
+use self::buttons::Buttons;
+use self::readback::PinContainer;
+use self::scratch::ScratchHashStore;
+use uc8151::frame_hash::{self, FrameCache};
+use uc8151::framebuffer::Framebuffer;
+use uc8151::profile::{PanelProfile, PANEL_2IN9_128X296};
+use uc8151::register::*;
+use uc8151::rotation::Rotation;
+#[cfg(feature = "trace")]
+use uc8151::trace::TraceBuffer;
+
+const PANEL: PanelProfile = PANEL_2IN9_128X296;
+type Frame = Framebuffer<{ PANEL.width.div_ceil(8) as usize }, { PANEL.height as usize }>;
 
 #[entry]
 fn main() -> ! {
@@ -105,17 +123,25 @@ fn main() -> ! {
 
     let mut led_pin = pins.led.into_push_pull_output();
 
+    #[cfg(any(feature = "bench-dtm", feature = "trace"))]
+    let timer = Timer::new(pac.TIMER, &mut pac.RESETS);
+
     led_pin.set_high().unwrap();
+    #[cfg_attr(feature = "trace", allow(unused_mut))]
     let mut display = Uc8151::new(
-        pins.gpio18, // sclk
-        pins.gpio19, // mosi
-        pins.gpio20, // dc
-        pins.gpio17, // cs
-        pins.gpio26, // busy
-        pins.gpio21, // reset
         spi,
+        pins.gpio20.into_push_pull_output(), // dc
+        pins.gpio17.into_push_pull_output(), // cs
+        pins.gpio26.into_pull_up_input(),    // busy
+        pins.gpio21.into_push_pull_output(), // reset
         delay,
+        PinContainer::new(pins.gpio18, pins.gpio19), // sclk, mosi
+        PANEL,
     );
+    #[cfg(feature = "trace")]
+    let mut display = display.with_trace(TraceBuffer::<_, { trace::TRACE_LEN }>::new(|| {
+        timer.get_counter() as u32
+    }));
     display.setup();
 
     led_pin.set_low().unwrap();
@@ -129,38 +155,50 @@ fn main() -> ! {
     display.command(0x92, []);
     display.busy_wait();
 
-    // Blank the display
-    // DTM2
-    display.data_start_transmission_2((0..4736).map(|_| 0x00));
-    // DSP
-    let _has_stopped = display.data_stop();
-    display.display_refresh(true);
+    #[cfg(feature = "bench-dtm")]
+    bench::dtm_transfer(&mut display, &timer, Frame::new().as_bytes());
+
+    // Blank the display, unless it still is from before a reset.
+    let mut frame = Frame::new();
+    let mut frame_cache = FrameCache::new(unsafe { ScratchHashStore::new() });
+    frame_cache.full_refresh(&mut display, &frame).unwrap();
 
     // Restart the power?
     // display.power_off();
     // display.power_on(true);
 
-    // Draw some stuff.
-    display.partial_in();
-    display.partial_window(PartialWindow {
-        horizontal_start_channel_bank: 5,
-        horizontal_end_channel_bank: 5,
-        vertical_start_line: 32,
-        vertical_end_line: 95,
-        partial_scan: true,
+    // Start on the name badge, with the badge held in landscape.
+    display.set_rotation(Rotation::Deg90);
+    frame.set_rotation(Rotation::Deg90);
+    let mut app = App::new(NameBadge {
+        heading: "BADGER 2040",
+        name: "Your Name",
+        detail: "written in Rust",
     });
-    display.data_start_transmission_2((0..64).map(|_| 0xff));
-    let has_stopped = display.data_stop();
-    display.display_refresh(true);
-    if !has_stopped {
-        led_pin.set_high().unwrap();
-    }
+    app.draw(&mut frame).unwrap();
+    frame.flush(&mut display).unwrap();
+    frame_cache.record(&display, frame_hash::hash(&frame));
+
+    let mut buttons = Buttons::new(
+        pins.gpio12.into_pull_down_input(),     // a
+        pins.gpio13.into_pull_down_input(),     // b
+        pins.gpio14.into_pull_down_input(),     // c
+        pins.gpio15.into_pull_down_input(),     // up
+        pins.gpio11.into_pull_down_input(),     // down
+        pins.b_power_save.into_pull_up_input(), // user, on GPIO23
+    );
     // display.command(0x61, &[48, 0, 0x20]);
     // display.command(0x65, &[40, 0, 0x0]);
     // let revision = display.revision();
 
-
     loop {
-
+        if let Some(button) = buttons.poll() {
+            app.press(button, &mut frame).unwrap();
+            frame.flush(&mut display).unwrap();
+            frame_cache.record(&display, frame_hash::hash(&frame));
+        }
+
+        #[cfg(feature = "trace")]
+        trace::serve(&mut usb_dev, &mut serial, display.trace().as_bytes());
     }
 }
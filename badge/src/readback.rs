@@ -0,0 +1,133 @@
+//! Reading from the UC8151 by taking the SPI clock and data pins over as GPIO.
+
+use crate::bsp::hal::gpio;
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+use uc8151::interface::Readback;
+
+struct WritePins<SclkPinId, MosiPinId>
+where
+    SclkPinId: gpio::PinId + gpio::bank0::BankPinId,
+    MosiPinId: gpio::PinId + gpio::bank0::BankPinId,
+{
+    sclk: gpio::Pin<SclkPinId, gpio::FunctionSpi>,
+    mosi: gpio::Pin<MosiPinId, gpio::FunctionSpi>,
+}
+
+impl<SclkPinId, MosiPinId> From<ReadPins<SclkPinId, MosiPinId>> for WritePins<SclkPinId, MosiPinId>
+where
+    SclkPinId: gpio::PinId + gpio::bank0::BankPinId,
+    MosiPinId: gpio::PinId + gpio::bank0::BankPinId,
+{
+    fn from(pins: ReadPins<SclkPinId, MosiPinId>) -> Self {
+        let sclk = pins.sclk.into_mode::<gpio::FunctionSpi>();
+        let mosi = pins.mosi.into_mode::<gpio::FunctionSpi>();
+        Self { sclk, mosi }
+    }
+}
+
+struct ReadPins<SclkPinId, MosiPinId>
+where
+    SclkPinId: gpio::PinId + gpio::bank0::BankPinId,
+    MosiPinId: gpio::PinId + gpio::bank0::BankPinId,
+{
+    sclk: gpio::Pin<SclkPinId, gpio::PushPullOutput>,
+    mosi: gpio::Pin<MosiPinId, gpio::Input<gpio::Floating>>,
+}
+
+impl<SclkPinId, MosiPinId> From<WritePins<SclkPinId, MosiPinId>> for ReadPins<SclkPinId, MosiPinId>
+where
+    SclkPinId: gpio::PinId + gpio::bank0::BankPinId,
+    MosiPinId: gpio::PinId + gpio::bank0::BankPinId,
+{
+    fn from(pins: WritePins<SclkPinId, MosiPinId>) -> Self {
+        let sclk = pins.sclk.into_push_pull_output();
+        let mosi = pins.mosi.into_floating_input();
+        Self { sclk, mosi }
+    }
+}
+
+enum PinMode<SclkPinId, MosiPinId>
+where
+    SclkPinId: gpio::PinId + gpio::bank0::BankPinId,
+    MosiPinId: gpio::PinId + gpio::bank0::BankPinId,
+{
+    Write(WritePins<SclkPinId, MosiPinId>),
+    Read(ReadPins<SclkPinId, MosiPinId>),
+}
+
+/// The SPI clock and data pins, in whichever mode was last needed. Only empty while the pins
+/// are being switched between modes.
+pub struct PinContainer<SclkPinId, MosiPinId>(Option<PinMode<SclkPinId, MosiPinId>>)
+where
+    SclkPinId: gpio::PinId + gpio::bank0::BankPinId,
+    MosiPinId: gpio::PinId + gpio::bank0::BankPinId;
+
+impl<SclkPinId, MosiPinId> PinContainer<SclkPinId, MosiPinId>
+where
+    SclkPinId: gpio::PinId + gpio::bank0::BankPinId,
+    MosiPinId: gpio::PinId + gpio::bank0::BankPinId,
+{
+    pub fn new<SclkPinMode, MosiPinMode>(
+        sclk_pin: gpio::Pin<SclkPinId, SclkPinMode>,
+        mosi_pin: gpio::Pin<MosiPinId, MosiPinMode>,
+    ) -> Self
+    where
+        SclkPinMode: gpio::ValidPinMode<SclkPinId> + gpio::PinMode,
+        MosiPinMode: gpio::ValidPinMode<MosiPinId> + gpio::PinMode,
+    {
+        // Move pins into write mode...
+        let sclk = sclk_pin.into_mode::<gpio::FunctionSpi>();
+        let mosi = mosi_pin.into_mode::<gpio::FunctionSpi>();
+        Self(Some(PinMode::Write(WritePins { sclk, mosi })))
+    }
+
+    fn get_write_pins(&mut self) -> &mut WritePins<SclkPinId, MosiPinId> {
+        let pins = match self.0.take() {
+            Some(PinMode::Read(pins)) => pins.into(),
+            Some(PinMode::Write(pins)) => pins,
+            None => unreachable!(),
+        };
+        match self.0.insert(PinMode::Write(pins)) {
+            PinMode::Write(pins) => pins,
+            PinMode::Read(_) => unreachable!(),
+        }
+    }
+
+    fn get_read_pins(&mut self) -> &mut ReadPins<SclkPinId, MosiPinId> {
+        let pins = match self.0.take() {
+            Some(PinMode::Write(pins)) => pins.into(),
+            Some(PinMode::Read(pins)) => pins,
+            None => unreachable!(),
+        };
+        match self.0.insert(PinMode::Read(pins)) {
+            PinMode::Read(pins) => pins,
+            PinMode::Write(_) => unreachable!(),
+        }
+    }
+}
+
+impl<SclkPinId, MosiPinId> Readback for PinContainer<SclkPinId, MosiPinId>
+where
+    SclkPinId: gpio::PinId + gpio::bank0::BankPinId,
+    MosiPinId: gpio::PinId + gpio::bank0::BankPinId,
+{
+    fn release(&mut self) {
+        self.get_write_pins();
+    }
+
+    fn acquire(&mut self) {
+        self.get_read_pins();
+    }
+
+    fn set_clock(&mut self, high: bool) {
+        let pins = self.get_read_pins();
+        match high {
+            true => pins.sclk.set_high().unwrap(),
+            false => pins.sclk.set_low().unwrap(),
+        }
+    }
+
+    fn data(&mut self) -> bool {
+        self.get_read_pins().mosi.is_high().unwrap()
+    }
+}
@@ -0,0 +1,55 @@
+//! Keeping the hash of the frame on the panel in the watchdog scratch registers.
+//!
+//! The scratch registers survive a watchdog or software reset, but not losing power. That is
+//! enough to skip redrawing after a reset, or after waking from sleep while on USB power, where
+//! the badge stays powered. Anything that fails the check reads as no hash at all.
+
+use crate::bsp::hal::pac;
+
+use uc8151::frame_hash::HashStore;
+
+// The bootrom uses scratch 4 to 7 when rebooting through the watchdog, so keep to 0 and 1.
+const CHECK: u32 = 0x6261_6467;
+
+pub struct ScratchHashStore {
+    _private: (),
+}
+
+impl ScratchHashStore {
+    /// # Safety
+    ///
+    /// Nothing else may use watchdog scratch registers 0 and 1.
+    pub unsafe fn new() -> Self {
+        Self { _private: () }
+    }
+
+    fn watchdog(&self) -> &pac::watchdog::RegisterBlock {
+        // The registers are only ever touched through this store, which is the only one.
+        unsafe { &*pac::WATCHDOG::ptr() }
+    }
+}
+
+impl HashStore for ScratchHashStore {
+    fn load(&mut self) -> Option<u32> {
+        let watchdog = self.watchdog();
+        let hash = watchdog.scratch0.read().bits();
+        let check = watchdog.scratch1.read().bits();
+        if check == hash ^ CHECK {
+            Some(hash)
+        } else {
+            None
+        }
+    }
+
+    fn store(&mut self, hash: u32) {
+        let watchdog = self.watchdog();
+        watchdog.scratch0.write(|w| unsafe { w.bits(hash) });
+        watchdog.scratch1.write(|w| unsafe { w.bits(hash ^ CHECK) });
+    }
+
+    fn clear(&mut self) {
+        let watchdog = self.watchdog();
+        watchdog.scratch0.write(|w| unsafe { w.bits(0) });
+        watchdog.scratch1.write(|w| unsafe { w.bits(0) });
+    }
+}
@@ -0,0 +1,40 @@
+//! Sending the driver's SPI trace to the host.
+//!
+//! Enabled with the `trace` feature. Every time the host sends anything over the USB serial
+//! port, the whole trace so far is written back, e.g.
+//! `echo > /dev/ttyACM0 & cat /dev/ttyACM0 > badge.trace`, then read with
+//! `host-tools trace badge.trace`. Once the buffer fills up, later events are dropped.
+
+use usb_device::class_prelude::UsbBus;
+use usb_device::prelude::*;
+use usbd_serial::SerialPort;
+
+/// Bytes of trace kept on the badge; enough for the setup and a few full refreshes.
+pub const TRACE_LEN: usize = 32 * 1024;
+
+/// Polls USB, and answers any request from the host with `trace`.
+pub fn serve<B: UsbBus>(
+    usb_dev: &mut UsbDevice<'_, B>,
+    serial: &mut SerialPort<'_, B>,
+    trace: &[u8],
+) {
+    if !usb_dev.poll(&mut [serial]) {
+        return;
+    }
+    let mut request = [0u8; 64];
+    match serial.read(&mut request) {
+        Ok(count) if count > 0 => {}
+        _ => return,
+    }
+
+    let mut remaining = trace;
+    while !remaining.is_empty() {
+        match serial.write(remaining) {
+            Ok(written) => remaining = &remaining[written..],
+            Err(UsbError::WouldBlock) => {
+                usb_dev.poll(&mut [serial]);
+            }
+            Err(_) => return,
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! Sends arbitrary commands and data to the emulated controller, which has to keep going
+//! whatever it is sent, reporting what it doesn't accept rather than panicking.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use uc8151_emulator::controller::Controller;
+
+fuzz_target!(|data: &[u8]| {
+    let mut controller = Controller::new();
+    // Each pair of bytes is what to do and the byte to send: mostly data, as on the real bus.
+    for pair in data.chunks_exact(2) {
+        match pair[0] {
+            0 => controller.reset(),
+            1..=31 => controller.command(pair[1]),
+            32..=63 => {
+                controller.poll_busy();
+            }
+            _ => controller.data(pair[1]),
+        }
+    }
+    controller.violations();
+    for y in 0..controller.height() {
+        for x in 0..controller.width() {
+            controller.pixel(x, y);
+        }
+    }
+});
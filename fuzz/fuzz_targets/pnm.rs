@@ -0,0 +1,14 @@
+//! Reads arbitrary bytes as a PBM or PGM image, checking that whatever reads comes out the same
+//! after being written as a PGM and read again.
+
+#![no_main]
+
+use host_tools::pnm;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(image) = pnm::read(data) {
+        assert_eq!(image.width * image.height, image.pixels.len());
+        assert_eq!(Ok(image.clone()), pnm::read(&pnm::write_pgm(&image)));
+    }
+});
@@ -0,0 +1,55 @@
+//! Decodes every register from arbitrary bytes, checking that encoding what was decoded and
+//! decoding it again gives the same setting.
+
+#![no_main]
+
+use std::convert::TryFrom;
+
+use libfuzzer_sys::fuzz_target;
+use uc8151::register::*;
+
+/// The first `N` bytes of `data`, padded with zeros.
+fn bytes<const N: usize>(data: &[u8]) -> [u8; N] {
+    let mut bytes = [0; N];
+    for (byte, &value) in bytes.iter_mut().zip(data) {
+        *byte = value;
+    }
+    bytes
+}
+
+fuzz_target!(|data: &[u8]| {
+    let panel_setting = PanelSetting::from(bytes::<1>(data));
+    assert_eq!(
+        panel_setting,
+        PanelSetting::from(<[u8; 1]>::from(panel_setting))
+    );
+
+    let booster = BoosterSoftStart::from(bytes::<3>(data));
+    assert_eq!(booster, BoosterSoftStart::from(<[u8; 3]>::from(booster)));
+
+    if let Ok(power) = PowerSetting::try_from(bytes::<5>(data)) {
+        assert_eq!(Ok(power), PowerSetting::try_from(<[u8; 5]>::from(power)));
+    }
+
+    let sequence = PowerOffSequence::from(bytes::<1>(data));
+    assert_eq!(sequence, PowerOffSequence::from(<[u8; 1]>::from(sequence)));
+
+    if let Ok(frequency) = PllClockFrequency::try_from(bytes::<1>(data)) {
+        assert_eq!(
+            Ok(frequency),
+            PllClockFrequency::try_from(<[u8; 1]>::from(frequency))
+        );
+    }
+
+    let resolution = ResolutionSetting::from(bytes::<3>(data));
+    assert_eq!(
+        resolution,
+        ResolutionSetting::from(<[u8; 3]>::from(resolution))
+    );
+
+    let window = PartialWindow::from(bytes::<7>(data));
+    assert_eq!(window, PartialWindow::from(<[u8; 7]>::from(window)));
+
+    let lut = LutSettingGroup::from(bytes::<42>(data));
+    assert_eq!(lut, LutSettingGroup::from(<[u8; 42]>::from(lut.clone())));
+});
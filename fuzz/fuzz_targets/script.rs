@@ -0,0 +1,9 @@
+//! Parses arbitrary text as a simulator script.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    let _ = badge_sim::script::parse(text);
+});
@@ -0,0 +1,20 @@
+//! Reads arbitrary bytes as a binary SPI trace, checking that whatever reads also survives
+//! being written as text and parsed again.
+
+#![no_main]
+
+use host_tools::trace;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    for record in uc8151::trace::records(data) {
+        if record.is_err() {
+            break;
+        }
+    }
+
+    if let Ok(entries) = trace::read(data) {
+        assert_eq!(Ok(entries.clone()), trace::parse(&trace::format(&entries)));
+        trace::replay(&entries);
+    }
+});
@@ -0,0 +1,13 @@
+//! Parses arbitrary text as a text SPI trace, checking that whatever parses comes out the same
+//! after being written and parsed again.
+
+#![no_main]
+
+use host_tools::trace;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    if let Ok(entries) = trace::parse(text) {
+        assert_eq!(Ok(entries.clone()), trace::parse(&trace::format(&entries)));
+    }
+});
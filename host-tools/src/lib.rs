@@ -0,0 +1,5 @@
+//! Tools for preparing images and other data for the badge on a development machine.
+
+pub mod pnm;
+pub mod snapshot;
+pub mod trace;
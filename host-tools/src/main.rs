@@ -0,0 +1,162 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fs;
+use std::process;
+
+use host_tools::pnm::{self, GrayImage};
+use host_tools::trace::{self, Entry};
+use uc8151::convert::{self, Dither, Image, PixelFormat};
+use uc8151_emulator::Pixel;
+
+const USAGE: &str = "\
+usage: host-tools convert [--gray] [--dither DITHER] INPUT OUTPUT
+       host-tools trace TRACE
+       host-tools replay TRACE [OUTPUT.pgm]
+
+Converts a PBM or PGM image into the UC8151's bit-planes: one plane for black and white, or
+with --gray the DTM1 plane followed by the DTM2 plane for a 4-level grayscale refresh. The
+planes are written as raw bytes, ready for include_bytes!, unless OUTPUT ends in .pbm, in which
+case the black and white result is written as an image to check it by eye.
+
+DITHER is one of threshold[=LEVEL] (the default, at 128), ordered or floyd-steinberg.
+
+trace prints an SPI trace dumped from a badge built with the trace feature as text. replay plays
+a trace into the emulator, lists the command sequences the controller would not have accepted,
+and writes what ended up on the panel to OUTPUT.pgm. A TRACE ending in .txt is read as text in
+the format trace prints, so captures of other drivers can be replayed too.";
+
+struct Convert {
+    gray: bool,
+    dither: Dither,
+    input: String,
+    output: String,
+}
+
+fn parse_dither(dither: &str) -> Result<Dither, String> {
+    match dither {
+        "threshold" => Ok(Dither::Threshold(128)),
+        "ordered" => Ok(Dither::Ordered),
+        "floyd-steinberg" => Ok(Dither::FloydSteinberg),
+        _ => match dither.strip_prefix("threshold=").map(str::parse) {
+            Some(Ok(level)) => Ok(Dither::Threshold(level)),
+            _ => Err(format!("unknown dither {:?}", dither)),
+        },
+    }
+}
+
+fn parse_convert(mut args: impl Iterator<Item = String>) -> Result<Convert, String> {
+    let mut gray = false;
+    let mut dither = Dither::Threshold(128);
+    let mut paths = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--gray" => gray = true,
+            "--dither" => {
+                let value = args.next().ok_or("--dither needs a value")?;
+                dither = parse_dither(&value)?;
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            _ => paths.push(arg),
+        }
+    }
+    match <[String; 2]>::try_from(paths) {
+        Ok([input, output]) => Ok(Convert {
+            gray,
+            dither,
+            input,
+            output,
+        }),
+        Err(_) => Err("expected an INPUT and an OUTPUT".into()),
+    }
+}
+
+fn convert(options: Convert) -> Result<(), Box<dyn Error>> {
+    let image = pnm::read(&fs::read(&options.input)?)?;
+    let source = Image::new(PixelFormat::Gray8, image.width, image.height, &image.pixels)
+        .map_err(|error| format!("{:?}", error))?;
+    let mut scratch = vec![0; convert::scratch_len(image.width)];
+    let plane_len = convert::plane_len(image.width, image.height);
+
+    let output = if options.gray {
+        let mut planes = vec![0; plane_len * 2];
+        let (plane_1, plane_2) = planes.split_at_mut(plane_len);
+        source
+            .pack_2bpp(options.dither, &mut scratch, plane_1, plane_2)
+            .map_err(|error| format!("{:?}", error))?;
+        planes
+    } else {
+        let mut plane = vec![0; plane_len];
+        source
+            .pack_1bpp(options.dither, &mut scratch, &mut plane)
+            .map_err(|error| format!("{:?}", error))?;
+        match options.output.ends_with(".pbm") {
+            true => pnm::write_pbm(image.width, image.height, &plane),
+            false => plane,
+        }
+    };
+
+    fs::write(&options.output, output)?;
+    Ok(())
+}
+
+fn read_trace(path: &str) -> Result<Vec<Entry>, Box<dyn Error>> {
+    let entries = match path.ends_with(".txt") {
+        true => trace::parse(&fs::read_to_string(path)?)?,
+        false => trace::read(&fs::read(path)?)?,
+    };
+    Ok(entries)
+}
+
+fn print_trace(mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let path = args.next().ok_or("expected a TRACE")?;
+    print!("{}", trace::format(&read_trace(&path)?));
+    Ok(())
+}
+
+fn replay(mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let path = args.next().ok_or("expected a TRACE")?;
+    let controller = trace::replay(&read_trace(&path)?);
+
+    for report in controller.violations() {
+        let name = trace::command_name(report.command).unwrap_or("unknown command");
+        println!(
+            "command {} ({:#04x}, {}): {:?}",
+            report.index, report.command, name, report.violation
+        );
+    }
+    println!("{} refreshes", controller.refreshes());
+
+    if let Some(output) = args.next() {
+        let (width, height) = (controller.width(), controller.height());
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(match controller.pixel(x, y) {
+                    Pixel::Gray(darkness) => 255 - darkness,
+                    Pixel::Red => 128,
+                });
+            }
+        }
+        let image = GrayImage {
+            width,
+            height,
+            pixels,
+        };
+        fs::write(output, pnm::write_pgm(&image))?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("convert") => parse_convert(args).map_err(Into::into).and_then(convert),
+        Some("trace") => print_trace(args),
+        Some("replay") => replay(args),
+        _ => Err(USAGE.into()),
+    };
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,199 @@
+//! Reading and writing the Netpbm formats: PBM for black and white and PGM for grayscale, in
+//! both their plain (ASCII) and raw (binary) forms.
+
+use std::fmt;
+
+use uc8151::convert;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PnmError {
+    /// Not a PBM or PGM file, or one of a kind that isn't supported, such as PPM.
+    Format,
+    /// The header is missing a field or has one that isn't a number.
+    Header,
+    /// There are fewer pixels than the header says.
+    Truncated,
+}
+
+impl fmt::Display for PnmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Format => write!(f, "not a PBM or PGM image"),
+            Self::Header => write!(f, "malformed header"),
+            Self::Truncated => write!(f, "image data is truncated"),
+        }
+    }
+}
+
+impl std::error::Error for PnmError {}
+
+/// An image as 8-bit grayscale, one byte per pixel from 0 for black to 255 for white.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrayImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn skip_space(&mut self) {
+        while let Some(&byte) = self.data.get(self.position) {
+            match byte {
+                b'#' => {
+                    while matches!(self.data.get(self.position), Some(&byte) if byte != b'\n') {
+                        self.position += 1;
+                    }
+                }
+                byte if byte.is_ascii_whitespace() => self.position += 1,
+                _ => break,
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<usize, PnmError> {
+        self.skip_space();
+        let start = self.position;
+        while matches!(self.data.get(self.position), Some(byte) if byte.is_ascii_digit()) {
+            self.position += 1;
+        }
+        std::str::from_utf8(&self.data[start..self.position])
+            .ok()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or(PnmError::Header)
+    }
+
+    /// A single `0` or `1`, which plain PBM allows to run together.
+    fn bit(&mut self) -> Result<bool, PnmError> {
+        self.skip_space();
+        let bit = match self.data.get(self.position) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(PnmError::Truncated),
+        };
+        self.position += 1;
+        Ok(bit)
+    }
+}
+
+/// Reads a PBM or PGM image, scaling gray levels to 0-255.
+pub fn read(data: &[u8]) -> Result<GrayImage, PnmError> {
+    let magic = data.get(..2).ok_or(PnmError::Format)?;
+    let mut reader = Reader { data, position: 2 };
+    let width = reader.number()?;
+    let height = reader.number()?;
+    let pixel_count = width.checked_mul(height).ok_or(PnmError::Header)?;
+
+    let pixels = match magic {
+        b"P1" => (0..pixel_count)
+            .map(|_| reader.bit().map(bit_to_gray))
+            .collect::<Result<_, _>>()?,
+        b"P4" => {
+            // Exactly one whitespace byte separates the header from the pixels.
+            let start = reader.position + 1;
+            let row_len = convert::plane_len(width, 1);
+            let rows = data
+                .get(start..start + row_len * height)
+                .ok_or(PnmError::Truncated)?;
+            rows.chunks(row_len)
+                .flat_map(|row| {
+                    (0..width).map(move |x| bit_to_gray(row[x / 8] & (0x80 >> (x % 8)) != 0))
+                })
+                .collect()
+        }
+        b"P2" => {
+            let max = reader.number()?.max(1);
+            (0..pixel_count)
+                .map(|_| reader.number().map(|value| scale(value, max)))
+                .collect::<Result<_, _>>()
+                .map_err(|_| PnmError::Truncated)?
+        }
+        b"P5" => {
+            let max = reader.number()?.max(1);
+            let start = reader.position + 1;
+            let sample_len = if max > 255 { 2 } else { 1 };
+            let samples_len = pixel_count
+                .checked_mul(sample_len)
+                .ok_or(PnmError::Truncated)?;
+            let samples = data
+                .get(start..start + samples_len)
+                .ok_or(PnmError::Truncated)?;
+            samples
+                .chunks(sample_len)
+                .map(|sample| {
+                    let value = sample
+                        .iter()
+                        .fold(0, |value, &byte| value << 8 | byte as usize);
+                    scale(value, max)
+                })
+                .collect()
+        }
+        _ => return Err(PnmError::Format),
+    };
+
+    Ok(GrayImage {
+        width,
+        height,
+        pixels,
+    })
+}
+
+fn bit_to_gray(black: bool) -> u8 {
+    match black {
+        true => 0,
+        false => 255,
+    }
+}
+
+fn scale(value: usize, max: usize) -> u8 {
+    (value.min(max) * 255 / max) as u8
+}
+
+/// Writes a raw PBM from MSB-first 1bpp rows with a set bit for black, which is both the
+/// UC8151's layout and PBM's own.
+pub fn write_pbm(width: usize, height: usize, plane: &[u8]) -> Vec<u8> {
+    let mut file = format!("P4\n{} {}\n", width, height).into_bytes();
+    file.extend_from_slice(&plane[..convert::plane_len(width, height)]);
+    file
+}
+
+/// Writes a raw PGM from 8-bit grayscale pixels.
+pub fn write_pgm(image: &GrayImage) -> Vec<u8> {
+    let mut file = format!("P5\n{} {}\n255\n", image.width, image.height).into_bytes();
+    file.extend_from_slice(&image.pixels);
+    file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_and_raw_pbm_match() {
+        let plain = read(b"P1\n# comment\n3 2\n1 0 1\n010\n").unwrap();
+        let raw = read(&write_pbm(3, 2, &[0b1010_0000, 0b0100_0000])).unwrap();
+        assert_eq!(plain, raw);
+        assert_eq!(plain.pixels, [0, 255, 0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn test_pgm_scales_to_255() {
+        let image = read(b"P2 2 1 15 0 15").unwrap();
+        assert_eq!(image.pixels, [0, 255]);
+        assert_eq!(read(&write_pgm(&image)).unwrap(), image);
+    }
+
+    #[test]
+    fn test_truncated() {
+        assert_eq!(read(b"P5 2 2 255\n\x00\x01"), Err(PnmError::Truncated));
+        assert_eq!(read(b"P6 1 1 255\n\x00\x00\x00"), Err(PnmError::Format));
+        assert_eq!(
+            read(b"P5 4294967296 4294967296 255\n"),
+            Err(PnmError::Header)
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Golden-image tests: checking a rendered image against a PGM committed next to the tests.
+//!
+//! Set `UPDATE_SNAPSHOTS=1` when running the tests to save what they render as the new golden
+//! images instead of comparing, then look the changed images over before committing them. When
+//! an image doesn't match, what was rendered is saved next to the golden image as
+//! `NAME.actual.pgm`, along with `NAME.diff.pgm`, which shows the golden image faintly with each
+//! pixel that differs in black.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use super::pnm::{self, GrayImage};
+
+/// Environment variable that makes [`assert_snapshot`] save images rather than compare them.
+pub const UPDATE_VAR: &str = "UPDATE_SNAPSHOTS";
+
+/// How the golden image is drawn in a diff, so that the differences stand out.
+const FAINT_BLACK: u8 = 192;
+
+/// Checks `image` against the golden image `NAME.pgm` in `dir`, or replaces it if
+/// [`UPDATE_VAR`] is set. Panics if they differ, or if there is no golden image yet.
+///
+/// Goldens keep every gray level, so grays and red pixels are checked as exactly as black and
+/// white ones.
+pub fn assert_snapshot(dir: &Path, name: &str, image: &GrayImage) {
+    let golden_path = dir.join(format!("{}.pgm", name));
+    let actual_path = dir.join(format!("{}.actual.pgm", name));
+    let diff_path = dir.join(format!("{}.diff.pgm", name));
+    let _ = fs::remove_file(&actual_path);
+    let _ = fs::remove_file(&diff_path);
+
+    if env::var_os(UPDATE_VAR).is_some() {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(&golden_path, pnm::write_pgm(image)).unwrap();
+        return;
+    }
+
+    let golden = match fs::read(&golden_path).map(|file| pnm::read(&file)) {
+        Ok(Ok(golden)) => golden,
+        Ok(Err(error)) => panic!("{}: {}", golden_path.display(), error),
+        Err(error) => panic!(
+            "{}: {}; run with {}=1 to create it",
+            golden_path.display(),
+            error,
+            UPDATE_VAR
+        ),
+    };
+
+    if (golden.width, golden.height) != (image.width, image.height) {
+        fs::write(&actual_path, pnm::write_pgm(image)).unwrap();
+        panic!(
+            "{} is {}x{}, but the image is {}x{}; it was saved as {}",
+            golden_path.display(),
+            golden.width,
+            golden.height,
+            image.width,
+            image.height,
+            actual_path.display()
+        );
+    }
+
+    if let Some((count, diff)) = diff(&golden, image) {
+        fs::write(&actual_path, pnm::write_pgm(image)).unwrap();
+        fs::write(&diff_path, pnm::write_pgm(&diff)).unwrap();
+        panic!(
+            "{} pixels differ from {}; see {} and {}",
+            count,
+            golden_path.display(),
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+/// The number of pixels that differ between two images of the same size, and an image showing
+/// where, or `None` if they are the same.
+pub fn diff(golden: &GrayImage, image: &GrayImage) -> Option<(usize, GrayImage)> {
+    let mut count = 0;
+    let pixels = golden
+        .pixels
+        .iter()
+        .zip(&image.pixels)
+        .map(
+            |(&expected, &actual)| match (expected == actual, expected) {
+                (false, _) => {
+                    count += 1;
+                    0
+                }
+                (true, 255) => 255,
+                (true, _) => FAINT_BLACK,
+            },
+        )
+        .collect();
+    match count {
+        0 => None,
+        _ => Some((
+            count,
+            GrayImage {
+                width: golden.width,
+                height: golden.height,
+                pixels,
+            },
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(pixels: &[u8]) -> GrayImage {
+        GrayImage {
+            width: 3,
+            height: 1,
+            pixels: pixels.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_diff_marks_changed_pixels() {
+        let golden = image(&[0, 255, 0]);
+        assert_eq!(None, diff(&golden, &golden.clone()));
+
+        let (count, diff) = diff(&golden, &image(&[0, 0, 128])).unwrap();
+        assert_eq!(2, count);
+        assert_eq!(vec![FAINT_BLACK, 0, 0], diff.pixels);
+    }
+
+    #[test]
+    fn test_gray_levels_match() {
+        let dir = env::temp_dir().join(format!("snapshot-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let golden = image(&[0, 85, 128]);
+        fs::write(dir.join("gray.pgm"), pnm::write_pgm(&golden)).unwrap();
+
+        assert_snapshot(&dir, "gray", &golden);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,354 @@
+//! Traces of what the driver sent, as recorded by `uc8151::trace::TraceBuffer` on the badge.
+//!
+//! Besides the binary format the badge records, traces can be written as text, one record per
+//! line, so they can be read, diffed, or put together by hand from a logic analyser capture of
+//! some other driver:
+//!
+//! ```text
+//! reset
+//! select
+//! command 00       # PANEL_SETTING
+//! data 1f          # resolution Res129x296, lut FromRegister, ...
+//! deselect
+//! busy 15230       # microseconds
+//! ```
+//!
+//! Anything after a `#` is a comment. Consecutive `data` or `read` lines belong together.
+
+use std::fmt::Write;
+
+use uc8151::register::constant::*;
+use uc8151::register::{PanelSetting, PartialWindow, ResolutionSetting};
+use uc8151::trace::{self, Record};
+use uc8151_emulator::Controller;
+
+/// Bytes of data per line of text.
+const BYTES_PER_LINE: usize = 16;
+
+/// A trace record that owns its bytes.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Entry {
+    Select,
+    Deselect,
+    Reset,
+    Command(u8),
+    Data(Vec<u8>),
+    Read(Vec<u8>),
+    Busy { us: u32 },
+}
+
+impl From<Record<'_>> for Entry {
+    fn from(record: Record) -> Self {
+        match record {
+            Record::Select => Self::Select,
+            Record::Deselect => Self::Deselect,
+            Record::Reset => Self::Reset,
+            Record::Command(command) => Self::Command(command),
+            Record::Data(bytes) => Self::Data(bytes.to_vec()),
+            Record::Read(bytes) => Self::Read(bytes.to_vec()),
+            Record::Busy { us } => Self::Busy { us },
+        }
+    }
+}
+
+/// Reads a binary trace.
+pub fn read(trace: &[u8]) -> Result<Vec<Entry>, String> {
+    let mut entries: Vec<Entry> = Vec::new();
+    for record in trace::records(trace) {
+        let entry = record.map_err(|error| format!("{:?}", error))?.into();
+        push(&mut entries, entry);
+    }
+    Ok(entries)
+}
+
+/// Adds `entry`, joining it onto the last one if they are both data or both reads.
+fn push(entries: &mut Vec<Entry>, entry: Entry) {
+    match (entries.last_mut(), entry) {
+        (Some(Entry::Data(bytes)), Entry::Data(more)) => bytes.extend(more),
+        (Some(Entry::Read(bytes)), Entry::Read(more)) => bytes.extend(more),
+        (_, entry) => entries.push(entry),
+    }
+}
+
+/// Reads a text trace.
+pub fn parse(text: &str) -> Result<Vec<Entry>, String> {
+    let mut entries = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("");
+        let mut words = line.split_whitespace();
+        let keyword = match words.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let error = |message: &str| format!("line {}: {}", number + 1, message);
+        let bytes = words
+            .clone()
+            .map(|word| u8::from_str_radix(word, 16))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| error("expected hex bytes"));
+        let entry = match keyword {
+            "select" => Entry::Select,
+            "deselect" => Entry::Deselect,
+            "reset" => Entry::Reset,
+            "command" => match bytes?.as_slice() {
+                &[command] => Entry::Command(command),
+                _ => return Err(error("expected one command byte")),
+            },
+            "data" => Entry::Data(bytes?),
+            "read" => Entry::Read(bytes?),
+            "busy" => match words.next().map(str::parse) {
+                Some(Ok(us)) => Entry::Busy { us },
+                _ => return Err(error("expected microseconds")),
+            },
+            _ => return Err(error(&format!("unknown record {:?}", keyword))),
+        };
+        push(&mut entries, entry);
+    }
+    Ok(entries)
+}
+
+/// The datasheet's name for `command`.
+pub fn command_name(command: u8) -> Option<&'static str> {
+    Some(match command {
+        PANEL_SETTING => "PANEL_SETTING",
+        POWER_SETTING => "POWER_SETTING",
+        POWER_OFF => "POWER_OFF",
+        POWER_OFF_SEQUENCE_SETTINGS => "POWER_OFF_SEQUENCE_SETTINGS",
+        POWER_ON => "POWER_ON",
+        POWER_ON_MEASURE => "POWER_ON_MEASURE",
+        BOOSTER_SOFT_START => "BOOSTER_SOFT_START",
+        DEEP_SLEEP => "DEEP_SLEEP",
+        DISPLAY_START_TRANSMISSION_1 => "DISPLAY_START_TRANSMISSION_1",
+        DATA_STOP => "DATA_STOP",
+        DISPLAY_REFRESH => "DISPLAY_REFRESH",
+        DISPLAY_START_TRANSMISSION_2 => "DISPLAY_START_TRANSMISSION_2",
+        VCOM_LUT => "VCOM_LUT",
+        W2W_LUT => "W2W_LUT",
+        B2W_LUT => "B2W_LUT",
+        W2B_LUT => "W2B_LUT",
+        B2B_LUT => "B2B_LUT",
+        PLL_CONTROL => "PLL_CONTROL",
+        TEMPERATURE_SENSOR_CALIBRATION => "TEMPERATURE_SENSOR_CALIBRATION",
+        TEMPERATURE_SENSOR_SELECTION => "TEMPERATURE_SENSOR_SELECTION",
+        TEMPERATURE_SENSOR_WRITE => "TEMPERATURE_SENSOR_WRITE",
+        TEMPERATURE_SENSOR_READ => "TEMPERATURE_SENSOR_READ",
+        VCOM_AND_DATA_INTERVAL_SETTING => "VCOM_AND_DATA_INTERVAL_SETTING",
+        LOWER_POWER_DETECTION => "LOWER_POWER_DETECTION",
+        TCON_SETTING => "TCON_SETTING",
+        RESOLUTION_SETTING => "RESOLUTION_SETTING",
+        REVISION => "REVISION",
+        GET_STATUS => "GET_STATUS",
+        AUTO_MEASUREMENT_VCOM => "AUTO_MEASUREMENT_VCOM",
+        READ_VCOM_VALUE => "READ_VCOM_VALUE",
+        VCOM_DC_SETTING => "VCOM_DC_SETTING",
+        PARTIAL_WINDOW => "PARTIAL_WINDOW",
+        PARTIAL_IN => "PARTIAL_IN",
+        PARTIAL_OUT => "PARTIAL_OUT",
+        PROGRAM_MOD => "PROGRAM_MOD",
+        ACTIVE_PROGRAMMING => "ACTIVE_PROGRAMMING",
+        READ_OTP => "READ_OTP",
+        CASCADE_SETTING => "CASCADE_SETTING",
+        POWER_SAVING => "POWER_SAVING",
+        FORCE_TEMPERATURE => "FORCE_TEMPERATURE",
+        _ => return None,
+    })
+}
+
+/// What the data for `command` means, for the registers that are worth decoding.
+fn describe(command: u8, data: &[u8]) -> Option<String> {
+    match (command, data) {
+        (PANEL_SETTING, &[byte]) => {
+            let setting = PanelSetting::from([byte]);
+            Some(format!(
+                "resolution {:?}, lut {:?}, {:?}, scan {:?}, shift {:?}",
+                setting.resolution,
+                setting.lut_selection,
+                setting.colour_selection,
+                setting.gate_scan_direction,
+                setting.source_shift_direction
+            ))
+        }
+        (RESOLUTION_SETTING, &[a, b, c]) => {
+            let setting = ResolutionSetting::from([a, b, c]);
+            Some(format!("{}x{}", setting.horizontal, setting.vertical))
+        }
+        (PARTIAL_WINDOW, &[a, b, c, d, e, f, g]) => {
+            let window = PartialWindow::from([a, b, c, d, e, f, g]);
+            Some(format!(
+                "banks {}-{}, lines {}-{}{}",
+                window.horizontal_start_channel_bank,
+                window.horizontal_end_channel_bank,
+                window.vertical_start_line,
+                window.vertical_end_line,
+                if window.partial_scan {
+                    ", partial scan"
+                } else {
+                    ""
+                }
+            ))
+        }
+        (DISPLAY_START_TRANSMISSION_1, _) | (DISPLAY_START_TRANSMISSION_2, _) => {
+            Some(format!("{} bytes", data.len()))
+        }
+        _ => None,
+    }
+}
+
+fn write_bytes(text: &mut String, keyword: &str, bytes: &[u8], comment: Option<String>) {
+    let mut comment = comment;
+    // Even no bytes at all get a line, so the record reads back.
+    let empty = bytes.is_empty().then_some(bytes);
+    for chunk in bytes.chunks(BYTES_PER_LINE).chain(empty) {
+        let mut line = keyword.to_string();
+        for byte in chunk {
+            write!(line, " {:02x}", byte).unwrap();
+        }
+        match comment.take() {
+            Some(comment) => writeln!(text, "{:<24} # {}", line, comment),
+            None => writeln!(text, "{}", line),
+        }
+        .unwrap();
+    }
+}
+
+/// Writes a trace as text, with comments naming each command and decoding some registers.
+pub fn format(entries: &[Entry]) -> String {
+    let mut text = String::new();
+    let mut command = None;
+    for entry in entries {
+        match entry {
+            Entry::Select => writeln!(text, "select"),
+            Entry::Deselect => writeln!(text, "deselect"),
+            Entry::Reset => writeln!(text, "reset"),
+            Entry::Command(byte) => {
+                command = Some(*byte);
+                match command_name(*byte) {
+                    Some(name) => {
+                        writeln!(text, "{:<24} # {}", format!("command {:02x}", byte), name)
+                    }
+                    None => writeln!(text, "command {:02x}", byte),
+                }
+            }
+            Entry::Data(bytes) => {
+                let comment = command.and_then(|command| describe(command, bytes));
+                write_bytes(&mut text, "data", bytes, comment);
+                Ok(())
+            }
+            Entry::Read(bytes) => {
+                write_bytes(&mut text, "read", bytes, None);
+                Ok(())
+            }
+            Entry::Busy { us } => writeln!(text, "{:<24} # microseconds", format!("busy {}", us)),
+        }
+        .unwrap();
+    }
+    text
+}
+
+/// Plays a trace into a fresh controller. Bytes sent while CS was high are dropped, as the
+/// controller would.
+///
+/// A trace only has the BUSY waits that took any time, and a driver may poll BUSY without
+/// waiting at all, so the controller is polled before every command and taken to be idle by
+/// then. Whether a driver waits for BUSY is checked when it runs against the emulator instead.
+pub fn replay(entries: &[Entry]) -> Controller {
+    let mut controller = Controller::new();
+    let mut selected = false;
+    for entry in entries {
+        match entry {
+            Entry::Select => selected = true,
+            Entry::Deselect => selected = false,
+            Entry::Reset => controller.reset(),
+            Entry::Command(command) if selected => {
+                controller.poll_busy();
+                controller.command(*command);
+            }
+            Entry::Data(bytes) if selected => bytes.iter().for_each(|&byte| controller.data(byte)),
+            Entry::Command(_) | Entry::Data(_) | Entry::Read(_) | Entry::Busy { .. } => {}
+        }
+    }
+    controller
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uc8151::framebuffer::{Colour, Framebuffer};
+    use uc8151::profile;
+    use uc8151::trace::TraceBuffer;
+    use uc8151_emulator::Emulator;
+
+    #[test]
+    fn test_text_round_trip() {
+        let entries = vec![
+            Entry::Reset,
+            Entry::Select,
+            Entry::Command(PANEL_SETTING),
+            Entry::Data(vec![0x1f]),
+            Entry::Deselect,
+            Entry::Select,
+            Entry::Command(DISPLAY_START_TRANSMISSION_2),
+            Entry::Data((0..40).collect()),
+            Entry::Deselect,
+            Entry::Busy { us: 15230 },
+            Entry::Read(vec![0x80]),
+            Entry::Command(DATA_STOP),
+            Entry::Read(vec![]),
+        ];
+        let text = format(&entries);
+        let lines: Vec<_> = text.lines().take(3).collect();
+        assert_eq!(
+            vec![
+                "reset",
+                "select",
+                "command 00               # PANEL_SETTING"
+            ],
+            lines
+        );
+        assert_eq!(Ok(entries), parse(&text));
+    }
+
+    #[test]
+    fn test_replay_without_busy_waits() {
+        // A refresh that was over by the time the driver next looked leaves no busy record.
+        let command = |command| vec![Entry::Select, Entry::Command(command), Entry::Deselect];
+        let entries = [
+            vec![Entry::Reset],
+            command(POWER_ON),
+            command(DISPLAY_REFRESH),
+            command(POWER_OFF),
+        ]
+        .concat();
+        let replayed = replay(&entries);
+        assert_eq!(1, replayed.refreshes());
+        assert!(replayed.violations().is_empty());
+    }
+
+    #[test]
+    fn test_replay_matches_the_emulator() {
+        let emulator = Emulator::new();
+        let mut display = emulator
+            .display(profile::PANEL_2IN9_128X296)
+            .with_trace(TraceBuffer::<_, 16384>::new(|| 0));
+        display.setup();
+        let mut frame = Framebuffer::<16, 296>::new();
+        for i in 0..128 {
+            frame.set_pixel(i, i * 2, Colour::Black);
+        }
+        display.full_refresh(&frame).unwrap();
+        assert!(!display.trace().overflowed());
+
+        let entries = read(display.trace().as_bytes()).unwrap();
+        assert_eq!(Ok(entries.clone()), parse(&format(&entries)));
+
+        let replayed = replay(&entries);
+        let controller = emulator.controller();
+        assert_eq!(controller.violations(), replayed.violations());
+        assert_eq!(controller.refreshes(), replayed.refreshes());
+        for y in 0..296 {
+            for x in 0..128 {
+                assert_eq!(controller.pixel(x, y), replayed.pixel(x, y));
+            }
+        }
+    }
+}
@@ -0,0 +1,540 @@
+//! The controller itself: registers, RAM and the panel it drives.
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use uc8151::register::*;
+
+use super::timing;
+use super::validation::{self, Report, Violation};
+
+/// Source outputs of the largest panel the controller can drive.
+pub const MAX_SOURCES: usize = 160;
+/// Gate lines of the largest panel the controller can drive.
+pub const MAX_GATES: usize = 296;
+
+const BANKS: usize = MAX_SOURCES / 8;
+
+/// How far a frame of VDH moves a pixel towards black, or VDL towards white, out of 255. A
+/// pixel goes all the way from white to black in 16 frames.
+const DRIVE_PER_FRAME: i32 = 16;
+
+/// Check code the deep sleep command must be followed by.
+const DEEP_SLEEP_CHECK: u8 = 0xa5;
+
+/// Data flag in the answer to a data stop command, set once a whole frame or window has arrived.
+const DATA_FLAG: u8 = 0x80;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Power {
+    Off,
+    On,
+    /// Only a hardware reset wakes the controller up again.
+    DeepSleep,
+}
+
+/// A pixel on the glass.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Pixel {
+    /// How far the pixel has been driven towards black, from 0 for white to 255 for black.
+    Gray(u8),
+    Red,
+}
+
+impl Pixel {
+    pub const WHITE: Pixel = Pixel::Gray(0);
+    pub const BLACK: Pixel = Pixel::Gray(255);
+
+    fn darkness(self) -> i32 {
+        match self {
+            Pixel::Gray(darkness) => darkness.into(),
+            Pixel::Red => 0,
+        }
+    }
+}
+
+/// Where data bytes for the last command are going.
+enum Destination {
+    /// Into a register, kept as sent.
+    Register(u8),
+    /// Into DTM1 or DTM2, this many bytes in.
+    Ram {
+        plane: usize,
+        offset: usize,
+    },
+    DeepSleep,
+    Nowhere,
+}
+
+/// The command data bytes are being counted for.
+struct Pending {
+    index: usize,
+    command: u8,
+    received: usize,
+}
+
+/// The area of RAM and of the panel a transfer or refresh covers, in banks and lines.
+#[derive(Copy, Clone)]
+struct Window {
+    first_bank: usize,
+    last_bank: usize,
+    first_line: usize,
+    last_line: usize,
+}
+
+impl Window {
+    fn banks(&self) -> usize {
+        (self.last_bank + 1).saturating_sub(self.first_bank)
+    }
+
+    fn len(&self) -> usize {
+        self.banks() * (self.last_line + 1).saturating_sub(self.first_line)
+    }
+}
+
+pub struct Controller {
+    registers: BTreeMap<u8, Vec<u8>>,
+    /// DTM1 and DTM2, each laid out as `BANKS` bytes per gate line.
+    ram: [Vec<u8>; 2],
+    destination: Destination,
+    partial: bool,
+    power: Power,
+    busy: bool,
+    data_flag: bool,
+    refreshes: u32,
+    /// Time BUSY has been held low by refreshes.
+    busy_us: u64,
+    panel: Vec<Pixel>,
+    /// Commands received so far, including ignored ones.
+    commands: usize,
+    pending: Option<Pending>,
+    reports: Vec<Report>,
+}
+
+impl Default for Controller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Controller {
+    /// A controller straight out of reset, in front of a white panel.
+    pub fn new() -> Self {
+        Self {
+            registers: BTreeMap::new(),
+            ram: [vec![0; BANKS * MAX_GATES], vec![0; BANKS * MAX_GATES]],
+            destination: Destination::Nowhere,
+            partial: false,
+            power: Power::Off,
+            busy: false,
+            data_flag: false,
+            refreshes: 0,
+            busy_us: 0,
+            panel: vec![Pixel::WHITE; MAX_SOURCES * MAX_GATES],
+            commands: 0,
+            pending: None,
+            reports: Vec::new(),
+        }
+    }
+
+    /// Hardware reset. Registers go back to their defaults, but the RAM and the image on the
+    /// panel are left as they were.
+    pub fn reset(&mut self) {
+        self.finish_command();
+        self.registers.clear();
+        self.destination = Destination::Nowhere;
+        self.partial = false;
+        self.power = Power::Off;
+        self.busy = false;
+        self.data_flag = false;
+    }
+
+    /// A byte sent with DC low.
+    pub fn command(&mut self, command: u8) {
+        self.finish_command();
+        let index = self.commands;
+        self.commands += 1;
+
+        let reports = &mut self.reports;
+        let mut report = |violation| {
+            reports.push(Report {
+                index,
+                command,
+                violation,
+            })
+        };
+        if self.busy {
+            report(Violation::Busy);
+        }
+        if self.power == Power::DeepSleep {
+            report(Violation::Asleep);
+            return;
+        }
+        match command {
+            constant::DISPLAY_REFRESH if self.power != Power::On => report(Violation::NotPowered),
+            constant::PARTIAL_WINDOW if !self.partial => report(Violation::NotPartial),
+            _ => {}
+        }
+        self.pending = Some(Pending {
+            index,
+            command,
+            received: 0,
+        });
+
+        self.destination = match command {
+            constant::DISPLAY_START_TRANSMISSION_1 | constant::DISPLAY_START_TRANSMISSION_2 => {
+                self.data_flag = false;
+                let plane = (command == constant::DISPLAY_START_TRANSMISSION_2) as usize;
+                Destination::Ram { plane, offset: 0 }
+            }
+            constant::DEEP_SLEEP => Destination::DeepSleep,
+            constant::POWER_ON | constant::POWER_OFF | constant::DISPLAY_REFRESH => {
+                Destination::Nowhere
+            }
+            constant::DATA_STOP | constant::PARTIAL_IN | constant::PARTIAL_OUT => {
+                Destination::Nowhere
+            }
+            _ => {
+                self.registers.insert(command, Vec::new());
+                Destination::Register(command)
+            }
+        };
+
+        match command {
+            constant::POWER_ON => {
+                self.power = Power::On;
+                self.busy = true;
+            }
+            constant::POWER_OFF => {
+                self.power = Power::Off;
+                self.busy = true;
+            }
+            constant::DISPLAY_REFRESH => {
+                self.busy_us += u64::from(self.refresh_us());
+                self.refresh();
+                self.busy = true;
+            }
+            constant::PARTIAL_IN => self.partial = true,
+            constant::PARTIAL_OUT => self.partial = false,
+            _ => {}
+        }
+    }
+
+    /// A byte sent with DC high, which belongs to the last command.
+    pub fn data(&mut self, byte: u8) {
+        if self.power == Power::DeepSleep {
+            return;
+        }
+        if let Some(pending) = self.pending.as_mut() {
+            pending.received += 1;
+        }
+
+        match self.destination {
+            Destination::Register(command) => {
+                if let Some(bytes) = self.registers.get_mut(&command) {
+                    bytes.push(byte);
+                }
+            }
+            Destination::Ram { plane, offset } => {
+                let window = self.window();
+                if offset < window.len() {
+                    let bank = window.first_bank + offset % window.banks();
+                    let line = window.first_line + offset / window.banks();
+                    self.ram[plane][line * BANKS + bank] = byte;
+                }
+                self.data_flag = offset + 1 >= window.len();
+                self.destination = Destination::Ram {
+                    plane,
+                    offset: offset + 1,
+                };
+            }
+            Destination::DeepSleep => {
+                if byte == DEEP_SLEEP_CHECK {
+                    self.power = Power::DeepSleep;
+                }
+            }
+            Destination::Nowhere => {}
+        }
+    }
+
+    /// Checks the payload of the last command, now that no more data can follow it.
+    fn finish_command(&mut self) {
+        if let Some(report) = self
+            .pending
+            .take()
+            .and_then(|pending| payload_report(&pending))
+        {
+            self.reports.push(report);
+        }
+    }
+
+    /// Everything the controller has been sent that it doesn't accept, in order. The payload of
+    /// the last command is checked as it stands.
+    pub fn violations(&self) -> Vec<Report> {
+        let mut reports = self.reports.clone();
+        reports.extend(self.pending.as_ref().and_then(payload_report));
+        reports
+    }
+
+    /// What the controller answers after `command`, most significant bit first.
+    pub fn answer(&self, command: u8) -> Vec<u8> {
+        match command {
+            constant::DATA_STOP if self.data_flag => vec![DATA_FLAG],
+            _ => vec![0],
+        }
+    }
+
+    /// Whether the controller is holding BUSY low. Each power change or refresh keeps it busy
+    /// until the next time BUSY is polled.
+    pub fn poll_busy(&mut self) -> bool {
+        let busy = self.busy;
+        self.busy = false;
+        busy
+    }
+
+    /// The bytes last written to the register set by `command`, if it has been written since
+    /// the last reset.
+    pub fn register(&self, command: u8) -> Option<&[u8]> {
+        self.registers.get(&command).map(Vec::as_slice)
+    }
+
+    /// The first `N` bytes of a register, if it has been written with at least that many.
+    fn register_array<const N: usize>(&self, command: u8) -> Option<[u8; N]> {
+        let bytes = self.register(command)?;
+        <[u8; N]>::try_from(bytes.get(..N)?).ok()
+    }
+
+    pub fn panel_setting(&self) -> PanelSetting {
+        self.register_array(constant::PANEL_SETTING)
+            .map(PanelSetting::from)
+            .unwrap_or_default()
+    }
+
+    pub fn resolution_setting(&self) -> Option<ResolutionSetting> {
+        self.register_array(constant::RESOLUTION_SETTING)
+            .map(ResolutionSetting::from)
+    }
+
+    pub fn partial_window(&self) -> Option<PartialWindow> {
+        self.register_array(constant::PARTIAL_WINDOW)
+            .map(PartialWindow::from)
+    }
+
+    /// The LUT loaded for one of [`constant::W2W_LUT`], [`constant::B2W_LUT`],
+    /// [`constant::W2B_LUT`] or [`constant::B2B_LUT`].
+    pub fn lut(&self, command: u8) -> Option<LutSettingGroup> {
+        self.register_array::<42>(command)
+            .map(LutSettingGroup::from)
+    }
+
+    /// Sources and gates in use, from the resolution setting if it has been written and the
+    /// panel setting otherwise. A resolution setting of zero is taken as one source or gate.
+    pub fn resolution(&self) -> (usize, usize) {
+        match self.resolution_setting() {
+            Some(setting) => (
+                usize::from(setting.horizontal).clamp(1, MAX_SOURCES),
+                usize::from(setting.vertical).clamp(1, MAX_GATES),
+            ),
+            None => {
+                let resolution = self.panel_setting().resolution;
+                (resolution.sources().into(), resolution.gates().into())
+            }
+        }
+    }
+
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    pub fn power(&self) -> Power {
+        self.power
+    }
+
+    /// Number of refreshes started, whether or not they could drive the panel.
+    pub fn refreshes(&self) -> u32 {
+        self.refreshes
+    }
+
+    /// Total time refreshes have held BUSY low since the controller was created.
+    pub fn busy_us(&self) -> u64 {
+        self.busy_us
+    }
+
+    /// How long BUSY would stay low for a refresh started now, from the LUTs, the frame rate
+    /// and, with partial scan, the lines in the partial window. See [`timing`].
+    pub fn refresh_us(&self) -> u32 {
+        if self.power != Power::On {
+            return 0;
+        }
+        let setting = self.panel_setting();
+        match (setting.colour_selection, setting.lut_selection) {
+            (ColourSelection::BlackWhiteRed, _) => return timing::OTP_BLACK_WHITE_RED_US,
+            (ColourSelection::BlackWhite, LutSelection::FromOtp) => {
+                return timing::OTP_BLACK_WHITE_US
+            }
+            (ColourSelection::BlackWhite, LutSelection::FromRegister) => {}
+        }
+
+        let frames = [
+            constant::VCOM_LUT,
+            constant::W2W_LUT,
+            constant::B2W_LUT,
+            constant::W2B_LUT,
+            constant::B2B_LUT,
+        ]
+        .iter()
+        .filter_map(|&command| self.lut(command))
+        .map(|lut| timing::frames(&lut))
+        .max()
+        .unwrap_or(0);
+        // A code the controller doesn't know is taken to leave the frame rate as it was.
+        let frame_us = self
+            .register_array(constant::PLL_CONTROL)
+            .and_then(|[code]| timing::frame_us(code))
+            .or_else(|| timing::frame_us(timing::RESET_PLL))
+            .unwrap_or(0);
+
+        let gates = self.height().max(1);
+        let lines = match self.partial_window() {
+            Some(window) if self.partial && window.partial_scan => {
+                let window = self.window();
+                (window.last_line + 1).saturating_sub(window.first_line)
+            }
+            _ => gates,
+        };
+        (u64::from(frames) * u64::from(frame_us) * lines as u64 / gates as u64) as u32
+    }
+
+    /// The bit for the pixel at `x`, `y` in DTM1 (`plane` 0) or DTM2 (`plane` 1).
+    pub fn ram_bit(&self, plane: usize, x: usize, y: usize) -> bool {
+        self.ram[plane][y * BANKS + x / 8] & 0x80 >> (x % 8) != 0
+    }
+
+    /// Width of the panel, i.e. the number of sources in use.
+    pub fn width(&self) -> usize {
+        self.resolution().0
+    }
+
+    /// Height of the panel, i.e. the number of gates in use.
+    pub fn height(&self) -> usize {
+        self.resolution().1
+    }
+
+    /// The pixel on the glass at `x`, `y`, counting from the first source and gate.
+    pub fn pixel(&self, x: usize, y: usize) -> Pixel {
+        self.panel[y * MAX_SOURCES + x]
+    }
+
+    /// The partial window when in partial mode, otherwise the whole panel.
+    fn window(&self) -> Window {
+        let (sources, gates) = self.resolution();
+        let full = Window {
+            first_bank: 0,
+            last_bank: sources.div_ceil(8) - 1,
+            first_line: 0,
+            last_line: gates - 1,
+        };
+        match (self.partial, self.partial_window()) {
+            (true, Some(window)) => Window {
+                first_bank: window.horizontal_start_channel_bank.into(),
+                last_bank: usize::from(window.horizontal_end_channel_bank).min(full.last_bank),
+                first_line: window.vertical_start_line.into(),
+                last_line: usize::from(window.vertical_end_line).min(full.last_line),
+            },
+            _ => full,
+        }
+    }
+
+    fn refresh(&mut self) {
+        self.refreshes += 1;
+        // Nothing moves without the charge pumps.
+        if self.power != Power::On {
+            return;
+        }
+
+        let setting = self.panel_setting();
+        let luts = [
+            constant::W2W_LUT,
+            constant::B2W_LUT,
+            constant::W2B_LUT,
+            constant::B2B_LUT,
+        ]
+        .map(|command| self.lut(command).unwrap_or_default());
+        let (sources, gates) = self.resolution();
+        let window = self.window();
+
+        for line in window.first_line..=window.last_line {
+            for x in window.first_bank * 8..((window.last_bank + 1) * 8).min(sources) {
+                let old = self.ram_bit(0, x, line);
+                let new = self.ram_bit(1, x, line);
+                let panel_x = match setting.source_shift_direction {
+                    SourceShiftDirection::Right => x,
+                    SourceShiftDirection::Left => sources - 1 - x,
+                };
+                let panel_y = match setting.gate_scan_direction {
+                    GateScanDirection::Down => line,
+                    GateScanDirection::Up => gates - 1 - line,
+                };
+                let pixel = &mut self.panel[panel_y * MAX_SOURCES + panel_x];
+
+                *pixel = match (setting.colour_selection, setting.lut_selection) {
+                    // Red panels always use their own waveforms from OTP, with DTM1 as the
+                    // black plane and DTM2 as the red one.
+                    (ColourSelection::BlackWhiteRed, _) => match (old, new) {
+                        (_, true) => Pixel::Red,
+                        (true, false) => Pixel::BLACK,
+                        (false, false) => Pixel::WHITE,
+                    },
+                    // The OTP waveforms drive every pixel to its new colour.
+                    (ColourSelection::BlackWhite, LutSelection::FromOtp) => match new {
+                        true => Pixel::BLACK,
+                        false => Pixel::WHITE,
+                    },
+                    (ColourSelection::BlackWhite, LutSelection::FromRegister) => {
+                        drive(*pixel, &luts[(new as usize) << 1 | old as usize])
+                    }
+                };
+            }
+        }
+    }
+}
+
+fn payload_report(pending: &Pending) -> Option<Report> {
+    let expected = validation::payload_len(pending.command)?;
+    if pending.received == expected {
+        return None;
+    }
+    Some(Report {
+        index: pending.index,
+        command: pending.command,
+        violation: Violation::PayloadLength {
+            expected,
+            received: pending.received,
+        },
+    })
+}
+
+/// Runs `pixel` through a waveform. VDH pushes it towards black and VDL towards white, a frame
+/// at a time, while GND and VDHR leave it where it is. VCOM is taken to sit at VCOM_DC.
+fn drive(pixel: Pixel, lut: &LutSettingGroup) -> Pixel {
+    let mut darkness = pixel.darkness();
+    for setting in lut.0.iter() {
+        let phases = [
+            (setting.level_select_1, setting.number_of_frames_1),
+            (setting.level_select_2, setting.number_of_frames_2),
+            (setting.level_select_3, setting.number_of_frames_3),
+            (setting.level_select_4, setting.number_of_frames_4),
+        ];
+        for _ in 0..setting.times_to_repeat {
+            for (level, frames) in phases.iter() {
+                let drive = match level {
+                    LevelSelection::Vdh => DRIVE_PER_FRAME,
+                    LevelSelection::Vdl => -DRIVE_PER_FRAME,
+                    LevelSelection::Gnd | LevelSelection::Vdhr => 0,
+                };
+                darkness = (darkness + drive * i32::from(*frames)).clamp(0, 255);
+            }
+        }
+    }
+    Pixel::Gray(darkness as u8)
+}
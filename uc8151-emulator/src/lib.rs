@@ -0,0 +1,17 @@
+//! A software model of the UC8151, for testing the driver on a machine with no panel attached.
+//!
+//! [`Controller`] takes the command and data bytes the driver sends and keeps track of the
+//! registers, both data transmission RAMs, the partial window and the power state, and draws the
+//! result onto a model of the glass whenever it is told to refresh. Along the way it reports any
+//! sequence a real controller wouldn't accept, see [`validation`], and works out how long each
+//! refresh would keep BUSY low, see [`timing`]. [`Emulator`] wraps it in mock SPI, GPIO and
+//! readback implementations so it can be handed straight to [`uc8151::Uc8151`].
+
+pub mod controller;
+pub mod mock;
+pub mod timing;
+pub mod validation;
+
+pub use controller::{Controller, Pixel, Power};
+pub use mock::{Display, Emulator, Line, Transfer};
+pub use validation::{Report, Violation};
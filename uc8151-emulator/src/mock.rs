@@ -0,0 +1,274 @@
+//! Mock SPI, GPIO and readback implementations that feed a [`Controller`].
+//!
+//! Every mock made from an [`Emulator`] shares its state, so the controller sees the bytes in
+//! the order the driver sent them and with the DC and CS levels it had set at the time. The
+//! transfers are also kept in a log for tests to look at.
+
+use std::cell::{Ref, RefCell};
+use std::convert::Infallible;
+use std::rc::Rc;
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi::{Write, WriteIter};
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+use embedded_hal::spi::FullDuplex;
+use uc8151::interface;
+use uc8151::profile::PanelProfile;
+use uc8151::Uc8151;
+
+use super::controller::Controller;
+
+/// The driver as wired up to an [`Emulator`].
+pub type Display = Uc8151<Spi, Pin, Pin, Busy, Pin, Delay, Readback>;
+
+/// A command and the data that followed it, including data sent after CS went high and low
+/// again.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Transfer {
+    pub command: u8,
+    pub data: Vec<u8>,
+}
+
+/// A bit-banged read in progress.
+struct Read {
+    answer: Vec<u8>,
+    clocks: usize,
+}
+
+struct State {
+    controller: Controller,
+    dc: bool,
+    cs: bool,
+    reset: bool,
+    /// The last command, for working out what to answer.
+    command: Option<u8>,
+    read: Option<Read>,
+    log: Vec<Transfer>,
+}
+
+impl State {
+    fn write(&mut self, byte: u8) {
+        // Deselected, held in reset, or the data line has been taken over for a read.
+        if self.cs || !self.reset || self.read.is_some() {
+            return;
+        }
+
+        match self.dc {
+            false => {
+                self.controller.command(byte);
+                self.command = Some(byte);
+                self.log.push(Transfer {
+                    command: byte,
+                    data: Vec::new(),
+                });
+            }
+            true => {
+                self.controller.data(byte);
+                if let Some(transfer) = self.log.last_mut() {
+                    transfer.data.push(byte);
+                }
+            }
+        }
+    }
+}
+
+/// Shared state behind all the mocks for one controller.
+#[derive(Clone)]
+pub struct Emulator(Rc<RefCell<State>>);
+
+impl Default for Emulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Emulator {
+    pub fn new() -> Self {
+        Self(Rc::new(RefCell::new(State {
+            controller: Controller::new(),
+            dc: false,
+            cs: true,
+            reset: true,
+            command: None,
+            read: None,
+            log: Vec::new(),
+        })))
+    }
+
+    /// The driver for `profile`, connected to this controller.
+    pub fn display(&self, profile: PanelProfile) -> Display {
+        Uc8151::new(
+            Spi(self.clone(), 0),
+            self.pin(Line::Dc),
+            self.pin(Line::Cs),
+            Busy(self.clone()),
+            self.pin(Line::Reset),
+            Delay,
+            Readback(self.clone()),
+            profile,
+        )
+    }
+
+    pub fn pin(&self, line: Line) -> Pin {
+        Pin(self.clone(), line)
+    }
+
+    pub fn controller(&self) -> Ref<'_, Controller> {
+        Ref::map(self.0.borrow(), |state| &state.controller)
+    }
+
+    /// Every transfer since the emulator was made or the log was last cleared.
+    pub fn log(&self) -> Ref<'_, [Transfer]> {
+        Ref::map(self.0.borrow(), |state| state.log.as_slice())
+    }
+
+    /// The data of the last transfer for `command`.
+    pub fn last(&self, command: u8) -> Option<Vec<u8>> {
+        self.log()
+            .iter()
+            .rev()
+            .find(|transfer| transfer.command == command)
+            .map(|transfer| transfer.data.clone())
+    }
+
+    pub fn clear_log(&self) {
+        self.0.borrow_mut().log.clear();
+    }
+}
+
+/// The SPI peripheral. Every byte written is also read back as a zero, as the controller never
+/// drives the data line during a write.
+pub struct Spi(Emulator, usize);
+
+impl Write<u8> for Spi {
+    type Error = Infallible;
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Infallible> {
+        self.write_iter(bytes.iter().copied())
+    }
+}
+
+impl WriteIter<u8> for Spi {
+    type Error = Infallible;
+
+    fn write_iter<WI: IntoIterator<Item = u8>>(&mut self, bytes: WI) -> Result<(), Infallible> {
+        let mut state = (self.0).0.borrow_mut();
+        for byte in bytes {
+            state.write(byte);
+        }
+        Ok(())
+    }
+}
+
+impl FullDuplex<u8> for Spi {
+    type Error = Infallible;
+
+    fn read(&mut self) -> nb::Result<u8, Infallible> {
+        match self.1 {
+            0 => Err(nb::Error::WouldBlock),
+            _ => {
+                self.1 -= 1;
+                Ok(0)
+            }
+        }
+    }
+
+    fn send(&mut self, byte: u8) -> nb::Result<(), Infallible> {
+        (self.0).0.borrow_mut().write(byte);
+        self.1 += 1;
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Line {
+    Dc,
+    Cs,
+    Reset,
+}
+
+pub struct Pin(Emulator, Line);
+
+impl OutputPin for Pin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        let mut state = (self.0).0.borrow_mut();
+        match self.1 {
+            Line::Dc => state.dc = false,
+            Line::Cs => state.cs = false,
+            Line::Reset => {
+                state.reset = false;
+                state.controller.reset();
+            }
+        }
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        let mut state = (self.0).0.borrow_mut();
+        match self.1 {
+            Line::Dc => state.dc = true,
+            Line::Cs => state.cs = true,
+            Line::Reset => state.reset = true,
+        }
+        Ok(())
+    }
+}
+
+/// The BUSY line, which is low while the controller is busy.
+pub struct Busy(Emulator);
+
+impl InputPin for Busy {
+    type Error = Infallible;
+
+    fn is_high(&self) -> Result<bool, Infallible> {
+        Ok(!(self.0).0.borrow_mut().controller.poll_busy())
+    }
+
+    fn is_low(&self) -> Result<bool, Infallible> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+/// Returns straight away. Nothing in the emulator takes any time.
+pub struct Delay;
+
+impl DelayMs<u32> for Delay {
+    fn delay_ms(&mut self, _ms: u32) {}
+}
+
+/// Clocks the controller's answer to the last command out of the data line.
+pub struct Readback(Emulator);
+
+impl interface::Readback for Readback {
+    fn release(&mut self) {
+        (self.0).0.borrow_mut().read = None;
+    }
+
+    fn acquire(&mut self) {
+        let mut state = (self.0).0.borrow_mut();
+        let answer = match state.command {
+            Some(command) => state.controller.answer(command),
+            None => Vec::new(),
+        };
+        state.read = Some(Read { answer, clocks: 0 });
+    }
+
+    fn set_clock(&mut self, high: bool) {
+        if let (true, Some(read)) = (high, (self.0).0.borrow_mut().read.as_mut()) {
+            read.clocks += 1;
+        }
+    }
+
+    /// The bit shifted out on the last rising edge of the clock.
+    fn data(&mut self) -> bool {
+        match &(self.0).0.borrow().read {
+            Some(Read { answer, clocks }) if *clocks > 0 => {
+                let bit = clocks - 1;
+                matches!(answer.get(bit / 8), Some(byte) if byte & 0x80 >> (bit % 8) != 0)
+            }
+            _ => false,
+        }
+    }
+}
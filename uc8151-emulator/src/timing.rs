@@ -0,0 +1,43 @@
+//! How long a refresh keeps BUSY low.
+//!
+//! The controller plays the waveform LUTs out a frame at a time, all of them in step, so a
+//! refresh takes as many frames as the longest LUT, at the frame rate set by the PLL control
+//! register. With partial scan, only the gate lines inside the partial window are scanned, and
+//! each frame is shorter in proportion.
+
+use uc8151::register::LutSettingGroup;
+
+/// PLL control setting after a reset, for 50Hz.
+pub const RESET_PLL: u8 = 0o74;
+
+/// Roughly how long the black/white waveform in OTP takes. The emulator can't see it, so this
+/// is what a refresh with [`uc8151::register::LutSelection::FromOtp`] is taken to last.
+pub const OTP_BLACK_WHITE_US: u32 = 2_000_000;
+
+/// Roughly how long the black/white/red waveform in OTP takes.
+pub const OTP_BLACK_WHITE_RED_US: u32 = 15_000_000;
+
+/// Length of one frame, scanning every gate line, at PLL control setting `code`. The frame
+/// rate is 200/7Hz times the multiplier in the high octal digit, divided by the divider in the
+/// low one. `None` if either digit is 0 or the code is out of range.
+pub fn frame_us(code: u8) -> Option<u32> {
+    let (m, n) = (u32::from(code >> 3), u32::from(code & 0o7));
+    if code >= 0o100 || m == 0 || n == 0 {
+        return None;
+    }
+    Some(7 * n * 1_000_000 / (200 * m))
+}
+
+/// Frames `lut` takes: every phase of every group, as many times as the group repeats.
+pub fn frames(lut: &LutSettingGroup) -> u32 {
+    lut.0
+        .iter()
+        .map(|setting| {
+            let phases = u32::from(setting.number_of_frames_1)
+                + u32::from(setting.number_of_frames_2)
+                + u32::from(setting.number_of_frames_3)
+                + u32::from(setting.number_of_frames_4);
+            phases * u32::from(setting.times_to_repeat)
+        })
+        .sum()
+}
@@ -0,0 +1,55 @@
+//! Command sequences the controller doesn't accept.
+//!
+//! A real UC8151 silently ignores or misbehaves on most of these, so the driver gets no
+//! feedback. The emulator reports each one against the command that caused it instead.
+
+use uc8151::register::constant;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Violation {
+    /// Sent during deep sleep, so the controller ignored it.
+    Asleep,
+    /// Sent while the controller was holding BUSY low.
+    Busy,
+    /// A refresh with the charge pumps off.
+    NotPowered,
+    /// The partial window was set before entering partial mode.
+    NotPartial,
+    /// The command was followed by the wrong number of data bytes.
+    PayloadLength { expected: usize, received: usize },
+}
+
+/// A violation and the command it happened at.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Report {
+    /// Position of the command among all those the controller has been sent, from 0.
+    pub index: usize,
+    pub command: u8,
+    pub violation: Violation,
+}
+
+/// Number of data bytes `command` takes, if it always takes the same number.
+pub fn payload_len(command: u8) -> Option<usize> {
+    match command {
+        constant::POWER_OFF
+        | constant::POWER_ON
+        | constant::POWER_ON_MEASURE
+        | constant::DATA_STOP
+        | constant::DISPLAY_REFRESH
+        | constant::PARTIAL_IN
+        | constant::PARTIAL_OUT => Some(0),
+        constant::PANEL_SETTING
+        | constant::POWER_OFF_SEQUENCE_SETTINGS
+        | constant::DEEP_SLEEP
+        | constant::PLL_CONTROL
+        | constant::TEMPERATURE_SENSOR_SELECTION
+        | constant::VCOM_AND_DATA_INTERVAL_SETTING
+        | constant::TCON_SETTING => Some(1),
+        constant::BOOSTER_SOFT_START | constant::RESOLUTION_SETTING => Some(3),
+        constant::POWER_SETTING => Some(5),
+        constant::PARTIAL_WINDOW => Some(7),
+        constant::W2W_LUT | constant::B2W_LUT | constant::W2B_LUT | constant::B2B_LUT => Some(42),
+        constant::VCOM_LUT => Some(44),
+        _ => None,
+    }
+}
@@ -0,0 +1,498 @@
+use uc8151::double_buffer::DoubleBuffered;
+use uc8151::frame_hash::{FrameCache, RamHashStore};
+use uc8151::framebuffer::{Colour, Framebuffer};
+use uc8151::grayscale::{GrayFramebuffer, GrayLevel};
+use uc8151::luts;
+use uc8151::profile::{self, PanelProfile};
+use uc8151::refresh_policy::{RefreshKind, RefreshPolicy};
+use uc8151::region::{Region, RegionError};
+use uc8151::register::*;
+use uc8151::rotation::Rotation;
+use uc8151::tri_colour::{TriColour, TriColourFramebuffer};
+use uc8151_emulator::{Display, Emulator, Pixel, Power, Report, Violation};
+
+type Frame = Framebuffer<16, 296>;
+
+fn set_up(profile: PanelProfile) -> (Emulator, Display) {
+    let emulator = Emulator::new();
+    let mut display = emulator.display(profile);
+    display.setup();
+    (emulator, display)
+}
+
+fn assert_valid(emulator: &Emulator) {
+    assert_eq!(Vec::<Report>::new(), emulator.controller().violations());
+}
+
+/// Checks every pixel on the panel against `frame`.
+fn assert_shows(emulator: &Emulator, frame: &Frame) {
+    let controller = emulator.controller();
+    for y in 0..296 {
+        for x in 0..128 {
+            let expected = match frame.get_pixel(x, y) {
+                Some(Colour::Black) => Pixel::BLACK,
+                _ => Pixel::WHITE,
+            };
+            assert_eq!(expected, controller.pixel(x, y), "pixel {}, {}", x, y);
+        }
+    }
+}
+
+#[test]
+fn setup_programs_the_panel() {
+    let (emulator, _display) = set_up(profile::PANEL_2IN9_128X296);
+    let controller = emulator.controller();
+
+    assert_eq!(Power::On, controller.power());
+    assert_eq!((128, 296), controller.resolution());
+    assert_eq!(None, controller.resolution_setting());
+
+    let setting = controller.panel_setting();
+    assert_eq!(Resolution::Res129x296, setting.resolution);
+    assert_eq!(LutSelection::FromRegister, setting.lut_selection);
+    assert_eq!(ColourSelection::BlackWhite, setting.colour_selection);
+
+    let power_setting: [u8; 5] = profile::PANEL_2IN9_128X296.power_setting.into();
+    assert_eq!(
+        Some(&power_setting[..]),
+        controller.register(constant::POWER_SETTING)
+    );
+    assert_eq!(
+        Some(&luts::DEFAULT.vcom[..]),
+        controller.register(constant::VCOM_LUT)
+    );
+    assert_eq!(
+        Some(&luts::DEFAULT.w2b[..]),
+        controller.register(constant::W2B_LUT)
+    );
+    assert_valid(&emulator);
+}
+
+#[test]
+fn setup_sets_resolution_for_other_sizes() {
+    let (emulator, _display) = set_up(profile::PANEL_2IN13_104X212);
+
+    assert_eq!(
+        Some(ResolutionSetting {
+            horizontal: 104,
+            vertical: 212
+        }),
+        emulator.controller().resolution_setting()
+    );
+    assert_eq!((104, 212), emulator.controller().resolution());
+    assert_valid(&emulator);
+}
+
+#[test]
+fn full_refresh_shows_the_frame() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+    let mut frame = Frame::new();
+    for i in 0..128 {
+        frame.set_pixel(i, i, Colour::Black);
+        frame.set_pixel(i, 295 - i / 2, Colour::Black);
+    }
+
+    display.full_refresh(&frame).unwrap();
+
+    assert_eq!(1, emulator.controller().refreshes());
+    assert_shows(&emulator, &frame);
+    assert_valid(&emulator);
+}
+
+#[test]
+fn update_region_only_redraws_the_region() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+    let mut frame = Frame::new();
+    display.full_refresh(&frame).unwrap();
+
+    frame.fill(Colour::Black);
+    let region = Region::new(16, 100, 24, 50);
+    display.update_region(region, &frame).unwrap();
+
+    let mut expected = Frame::new();
+    for y in 100..150 {
+        for x in 16..40 {
+            expected.set_pixel(x, y, Colour::Black);
+        }
+    }
+    assert_shows(&emulator, &expected);
+    assert!(!emulator.controller().is_partial());
+    assert_eq!(
+        Some(<[u8; 7]>::from(region.partial_window(128, 296).unwrap()).to_vec()),
+        emulator.last(constant::PARTIAL_WINDOW)
+    );
+    // Only the window is sent, a bank at a time.
+    assert_eq!(
+        Some(3 * 50),
+        emulator
+            .last(constant::DISPLAY_START_TRANSMISSION_2)
+            .map(|data| data.len())
+    );
+    assert_valid(&emulator);
+}
+
+#[test]
+fn refresh_policy_rejects_regions_off_the_panel() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+    let mut policy = RefreshPolicy::<4>::new(3, None);
+    let region = Region::new(65530, 0, 100, 1);
+
+    emulator.clear_log();
+    assert_eq!(
+        Err(RegionError::OutOfBounds),
+        policy.update(&mut display, region, &Frame::new(), 0)
+    );
+    assert!(emulator.log().is_empty());
+    assert_eq!(0, policy.updates(region));
+}
+
+#[test]
+fn refresh_policy_fast_refreshes_with_its_own_luts() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+    let mut policy = RefreshPolicy::<4>::new(2, None);
+    policy.fast_luts = Some(&luts::TURBO);
+    let mut frame = Frame::new();
+    let region = Region::new(0, 0, 8, 8);
+
+    frame.fill(Colour::Black);
+    for _ in 0..2 {
+        assert_eq!(
+            Ok(RefreshKind::Fast),
+            policy.update(&mut display, region, &frame, 0)
+        );
+    }
+    // Fast refreshes redraw everything, so count against every region.
+    assert_eq!(2, policy.updates(Region::new(64, 200, 8, 8)));
+    assert_eq!(
+        Ok(RefreshKind::Full),
+        policy.update(&mut display, region, &frame, 0)
+    );
+
+    assert_shows(&emulator, &frame);
+    assert_eq!(Some(&luts::DEFAULT), display.luts());
+    assert_valid(&emulator);
+}
+
+#[test]
+fn deep_clean_uses_the_full_refresh_luts() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+    display.turbo_luts();
+    let mut frame = Frame::new();
+    frame.fill(Colour::Black);
+    display.full_refresh(&frame).unwrap();
+
+    emulator.clear_log();
+    display.deep_clean(1);
+
+    let log = emulator.log();
+    let loaded = log
+        .iter()
+        .position(|transfer| {
+            transfer.command == constant::W2W_LUT && transfer.data == luts::DEFAULT.w2w
+        })
+        .unwrap();
+    let refresh = log
+        .iter()
+        .position(|transfer| transfer.command == constant::DISPLAY_REFRESH)
+        .unwrap();
+    assert!(loaded < refresh);
+    drop(log);
+
+    // One for the frame, then black and white.
+    assert_eq!(3, emulator.controller().refreshes());
+    assert_shows(&emulator, &Frame::new());
+    assert_eq!(Some(&luts::TURBO), display.luts());
+    assert_eq!(
+        Some(&luts::TURBO.w2w[..]),
+        emulator.controller().register(constant::W2W_LUT)
+    );
+    assert_valid(&emulator);
+}
+
+#[test]
+fn deep_clean_clears_the_black_plane_of_tri_colour_panels() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296_RED);
+    let mut frame = TriColourFramebuffer::<16, 296>::new();
+    frame.fill(TriColour::Black);
+    frame.set_pixel(5, 5, TriColour::Red);
+    display.display_tri_colour(&frame, true).unwrap();
+
+    display.deep_clean(1);
+
+    let controller = emulator.controller();
+    for (x, y) in [(0, 0), (5, 5), (127, 295)] {
+        assert_eq!(Pixel::WHITE, controller.pixel(x, y), "pixel {}, {}", x, y);
+    }
+    assert_valid(&emulator);
+}
+
+#[test]
+fn frame_cache_notices_other_refreshes() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+    let mut cache = FrameCache::new(RamHashStore::new());
+    let mut frame = Frame::new();
+
+    assert_eq!(Ok(true), cache.full_refresh(&mut display, &frame));
+    assert_eq!(Ok(false), cache.full_refresh(&mut display, &frame));
+
+    // Drawn without going through the cache, so the cached frame has to be sent again.
+    frame.set_pixel(0, 0, Colour::Black);
+    frame.flush(&mut display).unwrap();
+    frame.set_pixel(0, 0, Colour::White);
+    assert_eq!(Ok(true), cache.full_refresh(&mut display, &frame));
+
+    assert_eq!(3, emulator.controller().refreshes());
+    assert_shows(&emulator, &frame);
+}
+
+#[test]
+fn turbo_luts_replace_the_default_ones() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+
+    display.turbo_luts();
+
+    let controller = emulator.controller();
+    assert_eq!(
+        Some(&luts::TURBO.w2w[..]),
+        controller.register(constant::W2W_LUT)
+    );
+    assert_eq!(
+        Some(&luts::TURBO.b2b[..]),
+        controller.register(constant::B2B_LUT)
+    );
+    let pll: [u8; 1] = luts::TURBO.pll.into();
+    assert_eq!(Some(&pll[..]), controller.register(constant::PLL_CONTROL));
+    assert_valid(&emulator);
+}
+
+#[test]
+fn double_buffered_keeps_the_loaded_luts() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+    display.turbo_luts();
+    let mut frames = DoubleBuffered::<16, 296>::new();
+    frames.show(&mut display).unwrap();
+
+    emulator.clear_log();
+    frames.framebuffer_mut().fill(Colour::Black);
+    frames.show(&mut display).unwrap();
+
+    // The differential LUTs come from the profile's, not whatever was loaded.
+    let differential = luts::DEFAULT.differential();
+    assert!(
+        emulator
+            .log()
+            .iter()
+            .any(|transfer| transfer.command == constant::W2W_LUT
+                && transfer.data == differential.w2w)
+    );
+    assert_eq!(
+        &[0x00, 0x64, 0x64, 0x37, 0x00, 0x01],
+        &differential.w2w[..6]
+    );
+    assert_eq!(luts::DEFAULT.w2b, differential.w2b);
+
+    assert_shows(&emulator, frames.framebuffer());
+    assert_eq!(Some(&luts::TURBO), display.luts());
+    assert_eq!(
+        Some(&luts::TURBO.w2w[..]),
+        emulator.controller().register(constant::W2W_LUT)
+    );
+    assert_valid(&emulator);
+}
+
+#[test]
+fn grayscale_needs_register_luts_and_the_panel_size() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+    emulator.clear_log();
+    assert_eq!(
+        Err(RegionError::FramebufferSize),
+        display.display_grayscale(&GrayFramebuffer::<16, 212>::new())
+    );
+
+    let frame = GrayFramebuffer::<16, 296>::new();
+    let mut cache = FrameCache::new(RamHashStore::new());
+    let (red_emulator, mut red) = set_up(profile::PANEL_2IN9_128X296_RED);
+    assert_eq!(
+        Err(RegionError::ColourSelection),
+        cache.display_grayscale(&mut red, &frame)
+    );
+    // Black/white mode on a red panel still uses the LUTs in OTP.
+    red.set_colour_selection(ColourSelection::BlackWhite);
+    red.setup();
+    red_emulator.clear_log();
+    assert_eq!(
+        Err(RegionError::OtpLuts),
+        cache.display_grayscale(&mut red, &frame)
+    );
+    assert!(emulator.log().is_empty());
+    assert!(red_emulator.log().is_empty());
+}
+
+#[test]
+fn render_banded_grayscale_keeps_the_loaded_luts() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+    display.turbo_luts();
+    let mut buffer = Framebuffer::<16, 16>::new();
+
+    display
+        .render_banded_grayscale(&mut buffer, |band| band.set_pixel(3, 100, GrayLevel::Black))
+        .unwrap();
+
+    assert_eq!(Pixel::BLACK, emulator.controller().pixel(3, 100));
+    assert_eq!(Some(&luts::TURBO), display.luts());
+    assert_eq!(
+        Some(&luts::TURBO.w2w[..]),
+        emulator.controller().register(constant::W2W_LUT)
+    );
+    assert_valid(&emulator);
+}
+
+#[test]
+fn grayscale_shows_four_levels() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+    let mut frame = GrayFramebuffer::<16, 296>::new();
+    let levels = [
+        GrayLevel::White,
+        GrayLevel::LightGray,
+        GrayLevel::DarkGray,
+        GrayLevel::Black,
+    ];
+    for (x, level) in levels.iter().enumerate() {
+        frame.set_pixel(x, 0, *level);
+    }
+
+    display.display_grayscale(&frame).unwrap();
+
+    let controller = emulator.controller();
+    let shades: Vec<_> = (0..4).map(|x| controller.pixel(x, 0)).collect();
+    assert_eq!(Pixel::WHITE, shades[0]);
+    assert_eq!(Pixel::BLACK, shades[3]);
+    for pair in shades.windows(2) {
+        match pair {
+            [Pixel::Gray(lighter), Pixel::Gray(darker)] => assert!(lighter < darker),
+            _ => panic!("expected grays, got {:?}", pair),
+        }
+    }
+    // The black/white LUTs are put back afterwards.
+    assert_eq!(
+        Some(&luts::DEFAULT.w2w[..]),
+        controller.register(constant::W2W_LUT)
+    );
+    assert_valid(&emulator);
+}
+
+#[test]
+fn tri_colour_shows_red() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296_RED);
+    let mut frame = TriColourFramebuffer::<16, 296>::new();
+    frame.set_pixel(1, 0, TriColour::Black);
+    frame.set_pixel(2, 0, TriColour::Red);
+
+    display.display_tri_colour(&frame, true).unwrap();
+
+    let controller = emulator.controller();
+    assert_eq!(
+        LutSelection::FromOtp,
+        controller.panel_setting().lut_selection
+    );
+    assert_eq!(
+        [Pixel::WHITE, Pixel::BLACK, Pixel::Red],
+        [
+            controller.pixel(0, 0),
+            controller.pixel(1, 0),
+            controller.pixel(2, 0)
+        ]
+    );
+    assert_valid(&emulator);
+}
+
+#[test]
+fn tri_colour_needs_a_tri_colour_setup_and_the_panel_size() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+    emulator.clear_log();
+    let frame = TriColourFramebuffer::<16, 296>::new();
+
+    assert_eq!(
+        Err(RegionError::ColourSelection),
+        display.display_tri_colour(&frame, true)
+    );
+    assert!(emulator.log().is_empty());
+
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296_RED);
+    emulator.clear_log();
+    let mut cache = FrameCache::new(RamHashStore::new());
+    assert_eq!(
+        Err(RegionError::FramebufferSize),
+        cache.display_tri_colour(&mut display, &TriColourFramebuffer::<13, 212>::new(), true)
+    );
+    assert!(emulator.log().is_empty());
+}
+
+#[test]
+fn render_banded_keeps_the_buffer_rotation() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+    let mut buffer = Framebuffer::<16, 16>::new();
+    buffer.set_rotation(Rotation::Deg90);
+
+    display
+        .render_banded(&mut buffer, |band| band.set_pixel(5, 200, Colour::Black))
+        .unwrap();
+
+    let mut expected = Frame::new();
+    expected.set_pixel(5, 200, Colour::Black);
+    assert_shows(&emulator, &expected);
+    assert_eq!(Rotation::Deg90, buffer.rotation());
+    assert_valid(&emulator);
+}
+
+#[test]
+fn half_turn_is_done_by_the_controller() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+    let mut frame = Frame::new();
+    frame.set_pixel(0, 0, Colour::Black);
+
+    display.set_rotation(Rotation::Deg180);
+    display.full_refresh(&frame).unwrap();
+
+    let controller = emulator.controller();
+    assert_eq!(Pixel::BLACK, controller.pixel(127, 295));
+    assert_eq!(Pixel::WHITE, controller.pixel(0, 0));
+    assert_valid(&emulator);
+}
+
+#[test]
+fn deep_sleep_lasts_until_reset() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+
+    display.deep_sleep();
+    let asleep_from = emulator.log().len();
+    display.full_refresh(&Frame::new()).unwrap();
+    assert_eq!(Power::DeepSleep, emulator.controller().power());
+    assert_eq!(0, emulator.controller().refreshes());
+    assert_eq!(
+        Some(&Report {
+            index: asleep_from,
+            command: constant::DISPLAY_START_TRANSMISSION_2,
+            violation: Violation::Asleep,
+        }),
+        emulator.controller().violations().first()
+    );
+
+    emulator.clear_log();
+    display.setup();
+    assert_eq!(Power::On, emulator.controller().power());
+    let violations = emulator.controller().violations().len();
+    display.full_refresh(&Frame::new()).unwrap();
+    assert_eq!(violations, emulator.controller().violations().len());
+}
+
+#[test]
+fn lut_payloads_are_the_right_length() {
+    let (emulator, mut display) = set_up(profile::PANEL_2IN9_128X296);
+
+    display.custom_luts();
+    display.grayscale_luts();
+    display.turbo_luts();
+
+    assert_valid(&emulator);
+}
@@ -0,0 +1,115 @@
+use uc8151::framebuffer::Framebuffer;
+use uc8151::luts;
+use uc8151::profile;
+use uc8151::register::*;
+use uc8151_emulator::timing::{self, RESET_PLL};
+use uc8151_emulator::{Controller, Emulator};
+
+fn send(controller: &mut Controller, command: u8, data: &[u8]) {
+    controller.command(command);
+    for byte in data {
+        controller.data(*byte);
+    }
+}
+
+#[test]
+fn frame_rate_comes_from_the_pll_setting() {
+    assert_eq!(Some(20_000), timing::frame_us(RESET_PLL));
+    let frame_us = |frequency: PllClockFrequency| timing::frame_us(<[u8; 1]>::from(frequency)[0]);
+    assert_eq!(Some(10_000), frame_us(PllClockFrequency::_100Hz));
+    assert_eq!(Some(5_000), frame_us(PllClockFrequency::_200Hz));
+    assert_eq!(Some(35_000), frame_us(PllClockFrequency::_29Hz));
+    assert_eq!(None, timing::frame_us(0o70));
+    assert_eq!(None, timing::frame_us(0o107));
+}
+
+#[test]
+fn refresh_takes_as_long_as_the_longest_lut() {
+    let emulator = Emulator::new();
+    let mut display = emulator.display(profile::PANEL_2IN9_128X296);
+    display.setup();
+
+    // 255 + 4 * 280 + 255 frames at 100Hz.
+    assert_eq!(16_300_000, emulator.controller().refresh_us());
+
+    // 4 + 2 * 4 + 2 * 7 frames at 200Hz.
+    display.turbo_luts();
+    assert_eq!(130_000, emulator.controller().refresh_us());
+
+    let before = emulator.controller().busy_us();
+    let frame = Framebuffer::<16, 296>::new();
+    display.full_refresh(&frame).unwrap();
+    display.full_refresh(&frame).unwrap();
+    assert_eq!(before + 260_000, emulator.controller().busy_us());
+}
+
+#[test]
+fn partial_scan_only_takes_the_window_lines() {
+    let mut controller = Controller::new();
+    let setting = PanelSetting {
+        resolution: Resolution::Res129x296,
+        lut_selection: LutSelection::FromRegister,
+        colour_selection: ColourSelection::BlackWhite,
+        ..PanelSetting::default()
+    };
+    send(
+        &mut controller,
+        constant::PANEL_SETTING,
+        &<[u8; 1]>::from(setting),
+    );
+    send(&mut controller, constant::VCOM_LUT, &luts::TURBO.vcom);
+    send(&mut controller, constant::W2W_LUT, &luts::TURBO.w2w);
+    send(
+        &mut controller,
+        constant::PLL_CONTROL,
+        &<[u8; 1]>::from(luts::TURBO.pll),
+    );
+    send(&mut controller, constant::POWER_ON, &[]);
+
+    send(&mut controller, constant::PARTIAL_IN, &[]);
+    let window = |partial_scan| {
+        let window = PartialWindow::new(0, 15, 0, 73, partial_scan, 128, 296);
+        <[u8; 7]>::from(window.unwrap())
+    };
+    send(&mut controller, constant::PARTIAL_WINDOW, &window(false));
+    assert_eq!(130_000, controller.refresh_us());
+
+    // A quarter of the 296 lines.
+    send(&mut controller, constant::PARTIAL_WINDOW, &window(true));
+    assert_eq!(32_500, controller.refresh_us());
+
+    send(&mut controller, constant::PARTIAL_OUT, &[]);
+    assert_eq!(130_000, controller.refresh_us());
+}
+
+#[test]
+fn otp_waveforms_take_a_fixed_time() {
+    let emulator = Emulator::new();
+    let mut display = emulator.display(profile::PANEL_2IN9_128X296_RED);
+    display.setup();
+    assert_eq!(
+        timing::OTP_BLACK_WHITE_RED_US,
+        emulator.controller().refresh_us()
+    );
+
+    let mut controller = Controller::new();
+    let setting = PanelSetting {
+        colour_selection: ColourSelection::BlackWhite,
+        ..PanelSetting::default()
+    };
+    send(
+        &mut controller,
+        constant::PANEL_SETTING,
+        &<[u8; 1]>::from(setting),
+    );
+    send(&mut controller, constant::POWER_ON, &[]);
+    assert_eq!(timing::OTP_BLACK_WHITE_US, controller.refresh_us());
+}
+
+#[test]
+fn nothing_moves_without_power() {
+    let mut controller = Controller::new();
+    send(&mut controller, constant::VCOM_LUT, &luts::DEFAULT.vcom);
+    send(&mut controller, constant::DISPLAY_REFRESH, &[]);
+    assert_eq!(0, controller.busy_us());
+}
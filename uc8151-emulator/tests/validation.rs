@@ -0,0 +1,129 @@
+use uc8151::register::constant::*;
+use uc8151_emulator::{Controller, Report, Violation};
+
+fn send(controller: &mut Controller, command: u8, data: &[u8]) {
+    controller.command(command);
+    for byte in data {
+        controller.data(*byte);
+    }
+}
+
+fn report(index: usize, command: u8, violation: Violation) -> Report {
+    Report {
+        index,
+        command,
+        violation,
+    }
+}
+
+#[test]
+fn refresh_needs_power() {
+    let mut controller = Controller::new();
+    send(&mut controller, DISPLAY_START_TRANSMISSION_2, &[0; 16]);
+    send(&mut controller, DISPLAY_REFRESH, &[]);
+
+    assert_eq!(
+        vec![report(1, DISPLAY_REFRESH, Violation::NotPowered)],
+        controller.violations()
+    );
+}
+
+#[test]
+fn partial_window_needs_partial_mode() {
+    let mut controller = Controller::new();
+    send(
+        &mut controller,
+        PARTIAL_WINDOW,
+        &[0x00, 0x07, 0, 0, 0, 7, 0x01],
+    );
+    send(&mut controller, PARTIAL_IN, &[]);
+    send(
+        &mut controller,
+        PARTIAL_WINDOW,
+        &[0x00, 0x07, 0, 0, 0, 7, 0x01],
+    );
+
+    assert_eq!(
+        vec![report(0, PARTIAL_WINDOW, Violation::NotPartial)],
+        controller.violations()
+    );
+}
+
+#[test]
+fn lut_payload_length() {
+    let mut controller = Controller::new();
+    send(&mut controller, W2W_LUT, &[0; 49]);
+    send(&mut controller, B2W_LUT, &[0; 42]);
+    send(&mut controller, VCOM_LUT, &[0; 42]);
+
+    let violations = controller.violations();
+    assert_eq!(
+        vec![
+            report(
+                0,
+                W2W_LUT,
+                Violation::PayloadLength {
+                    expected: 42,
+                    received: 49
+                }
+            ),
+            // The last command is checked as it stands.
+            report(
+                2,
+                VCOM_LUT,
+                Violation::PayloadLength {
+                    expected: 44,
+                    received: 42
+                }
+            ),
+        ],
+        violations
+    );
+}
+
+#[test]
+fn commands_while_busy() {
+    let mut controller = Controller::new();
+    send(&mut controller, POWER_ON, &[]);
+    send(&mut controller, PLL_CONTROL, &[0x3a]);
+    assert!(controller.poll_busy());
+    send(&mut controller, POWER_OFF, &[]);
+    assert!(controller.poll_busy());
+    assert!(!controller.poll_busy());
+    send(&mut controller, POWER_ON, &[]);
+
+    assert_eq!(
+        vec![report(1, PLL_CONTROL, Violation::Busy)],
+        controller.violations()
+    );
+}
+
+#[test]
+fn writes_during_deep_sleep() {
+    let mut controller = Controller::new();
+    send(&mut controller, DEEP_SLEEP, &[0xa5]);
+    send(&mut controller, DISPLAY_START_TRANSMISSION_1, &[0xff; 16]);
+    controller.reset();
+    send(&mut controller, DISPLAY_START_TRANSMISSION_1, &[0xff; 16]);
+
+    assert_eq!(
+        vec![report(1, DISPLAY_START_TRANSMISSION_1, Violation::Asleep)],
+        controller.violations()
+    );
+    assert!(controller.ram_bit(0, 0, 0));
+}
+
+#[test]
+fn zero_resolution() {
+    let mut controller = Controller::new();
+    send(&mut controller, RESOLUTION_SETTING, &[0, 0, 0]);
+    send(&mut controller, DISPLAY_START_TRANSMISSION_1, &[0xff]);
+    send(&mut controller, DISPLAY_REFRESH, &[]);
+
+    assert_eq!((1, 1), controller.resolution());
+    assert!(controller.ram_bit(0, 0, 0));
+    assert_eq!(
+        vec![report(2, DISPLAY_REFRESH, Violation::NotPowered)],
+        controller.violations()
+    );
+}
@@ -0,0 +1,193 @@
+//! Rendering whole frames through a buffer that holds only a few lines.
+//!
+//! Instead of drawing into a framebuffer the size of the panel, the application draws into a
+//! [`Band`] from a callback. The driver calls it once for each horizontal band of gate lines, top
+//! to bottom, and streams every band into the controller as soon as it has been drawn. A 16 line
+//! band for the 128x296 panel is 256 bytes instead of 4736.
+//!
+//! The callback has to draw the whole image every time, since each call only keeps what lands
+//! in its band. Grayscale and tri-colour images are sent one bit-plane at a time, so for those
+//! the callback is called twice for every band.
+
+use embedded_hal::blocking::delay::DelayMs;
+
+use super::framebuffer::{Colour, Framebuffer};
+use super::grayscale::GrayLevel;
+use super::interface::{Bus, Input, Output, Readback};
+use super::region::RegionError;
+use super::rotation::Rotation;
+use super::trace::Trace;
+use super::tri_colour::TriColour;
+use super::Uc8151;
+
+/// A window onto some of the panel's gate lines, drawn to in the coordinates of the current
+/// rotation. Anything outside the band is ignored.
+pub struct Band<'a, C, const BANKS: usize, const LINES: usize> {
+    buffer: &'a mut Framebuffer<BANKS, LINES>,
+    first_line: usize,
+    panel_lines: usize,
+    rotation: Rotation,
+    plane: fn(C) -> Colour,
+}
+
+impl<'a, C, const BANKS: usize, const LINES: usize> Band<'a, C, BANKS, LINES> {
+    /// The first gate line in this band.
+    pub fn first_line(&self) -> usize {
+        self.first_line
+    }
+
+    /// Width of the whole image in the current rotation.
+    pub fn width(&self) -> usize {
+        self.rotation.size(BANKS * 8, self.panel_lines).0
+    }
+
+    /// Height of the whole image in the current rotation.
+    pub fn height(&self) -> usize {
+        self.rotation.size(BANKS * 8, self.panel_lines).1
+    }
+
+    /// Sets a pixel of the whole image, ignoring it if it is outside this band.
+    pub fn set_pixel(&mut self, x: usize, y: usize, colour: C) {
+        if x >= self.width() || y >= self.height() {
+            return;
+        }
+        let (x, line) = self.rotation.to_panel(x, y, BANKS * 8);
+        if (self.first_line..self.first_line + LINES).contains(&line) {
+            self.buffer
+                .write_pixel(x, line - self.first_line, (self.plane)(colour));
+        }
+    }
+}
+
+fn colour(bit: bool) -> Colour {
+    match bit {
+        false => Colour::White,
+        true => Colour::Black,
+    }
+}
+
+impl<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>
+    Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>
+where
+    SPI: Bus,
+    DC: Output,
+    CS: Output,
+    BUSY: Input,
+    RESET: Output,
+    DELAY: DelayMs<u32>,
+    READBACK: Readback,
+    TRACE: Trace,
+{
+    /// Sends one bit-plane, drawn band by band into `buffer`, as the data for the last command.
+    ///
+    /// The buffer's pixels are left as whatever was drawn last, but its rotation is put back.
+    fn stream_bands<C, F, const BANKS: usize, const LINES: usize>(
+        &mut self,
+        buffer: &mut Framebuffer<BANKS, LINES>,
+        plane: fn(C) -> Colour,
+        draw: &mut F,
+    ) where
+        F: FnMut(&mut Band<C, BANKS, LINES>),
+    {
+        let panel_lines = self.profile.height as usize;
+        let rotation = buffer.rotation();
+        buffer.set_rotation(Rotation::Deg0);
+
+        for first_line in (0..panel_lines).step_by(LINES) {
+            buffer.fill(Colour::White);
+            draw(&mut Band {
+                buffer: &mut *buffer,
+                first_line,
+                panel_lines,
+                rotation: self.rotation,
+                plane,
+            });
+
+            let lines = LINES.min(panel_lines - first_line);
+            self.data_slice(&buffer.as_bytes()[..lines * BANKS]);
+        }
+        buffer.set_rotation(rotation);
+        buffer.mark_clean();
+    }
+
+    fn check_band<const BANKS: usize, const LINES: usize>(
+        &self,
+        _buffer: &Framebuffer<BANKS, LINES>,
+    ) -> Result<(), RegionError> {
+        let banks = (self.profile.width as usize).div_ceil(8);
+        if BANKS != banks || LINES == 0 {
+            return Err(RegionError::FramebufferSize);
+        }
+        Ok(())
+    }
+
+    /// Like [`Uc8151::full_refresh`], but drawing the frame band by band through `buffer`, which
+    /// must be as wide as the panel and may have as few lines as you like. The buffer is used as
+    /// scratch space, so its pixels are overwritten, but it keeps its rotation.
+    pub fn render_banded<F, const BANKS: usize, const LINES: usize>(
+        &mut self,
+        buffer: &mut Framebuffer<BANKS, LINES>,
+        mut draw: F,
+    ) -> Result<(), RegionError>
+    where
+        F: FnMut(&mut Band<Colour, BANKS, LINES>),
+    {
+        self.check_band(buffer)?;
+
+        self.data_start_transmission_2([]);
+        self.stream_bands(buffer, |colour| colour, &mut draw);
+        self.data_stop();
+        self.display_refresh(true);
+
+        Ok(())
+    }
+
+    /// Like [`Uc8151::display_grayscale`], but drawing the frame band by band through `buffer`.
+    /// The LUTs that were loaded before are put back afterwards.
+    pub fn render_banded_grayscale<F, const BANKS: usize, const LINES: usize>(
+        &mut self,
+        buffer: &mut Framebuffer<BANKS, LINES>,
+        mut draw: F,
+    ) -> Result<(), RegionError>
+    where
+        F: FnMut(&mut Band<GrayLevel, BANKS, LINES>),
+    {
+        self.check_band(buffer)?;
+        self.check_register_luts()?;
+
+        let luts = self.luts.take();
+        self.grayscale_luts();
+        self.data_start_transmission_1([]);
+        self.stream_bands(buffer, |level| colour(level.plane_1_bit()), &mut draw);
+        self.data_start_transmission_2([]);
+        self.stream_bands(buffer, |level| colour(level.plane_2_bit()), &mut draw);
+        self.data_stop();
+        self.display_refresh(true);
+        self.restore_luts(luts);
+
+        Ok(())
+    }
+
+    /// Like [`Uc8151::display_tri_colour`], but drawing the frame band by band through `buffer`.
+    pub fn render_banded_tri_colour<F, const BANKS: usize, const LINES: usize>(
+        &mut self,
+        buffer: &mut Framebuffer<BANKS, LINES>,
+        blocking: bool,
+        mut draw: F,
+    ) -> Result<(), RegionError>
+    where
+        F: FnMut(&mut Band<TriColour, BANKS, LINES>),
+    {
+        self.check_band(buffer)?;
+        self.check_tri_colour()?;
+
+        self.data_start_transmission_1([]);
+        self.stream_bands(buffer, |colour: TriColour| colour.bits().0, &mut draw);
+        self.data_start_transmission_2([]);
+        self.stream_bands(buffer, |colour: TriColour| colour.bits().1, &mut draw);
+        self.data_stop();
+        self.display_refresh(blocking);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,385 @@
+//! Converting images from other pixel formats into the controller's bit-planes.
+//!
+//! An [`Image`] wraps pixels in one of the common [`PixelFormat`]s. It can be packed into the
+//! MSB-first 1bpp layout DTM1 and DTM2 take, into the pair of planes used for 4-level gray, or
+//! drawn onto one of the framebuffers. Nothing here touches the hardware, so the same code
+//! works on the badge and on a host preparing images for it.
+//!
+//! Reducing to black and white or four grays can be done with a plain threshold, an ordered
+//! (Bayer) dither or Floyd-Steinberg error diffusion. Error diffusion needs to remember the error
+//! for the next row, so it takes a scratch buffer of [`scratch_len`] entries from the caller.
+
+use super::framebuffer::{Colour, Framebuffer};
+use super::grayscale::{GrayFramebuffer, GrayLevel};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PixelFormat {
+    /// One byte per pixel, 0 for black up to 255 for white.
+    Gray8,
+    /// Two bytes per pixel, 5 bits red, 6 green and 5 blue, little-endian.
+    Rgb565Le,
+    /// Two bytes per pixel, 5 bits red, 6 green and 5 blue, big-endian.
+    Rgb565Be,
+    /// One bit per pixel, set for black, with the leftmost pixel of each byte in bit 0. Rows are
+    /// padded to a whole number of bytes, as in XBM files.
+    Mono1Lsb,
+}
+
+impl PixelFormat {
+    /// Bytes taken by a row `width` pixels across.
+    pub const fn row_len(self, width: usize) -> usize {
+        match self {
+            Self::Gray8 => width,
+            Self::Rgb565Le | Self::Rgb565Be => width * 2,
+            Self::Mono1Lsb => width.div_ceil(8),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Dither {
+    /// Each pixel goes to the nearest level after adding `128 - threshold` to its brightness, so
+    /// for black and white anything darker than the threshold is black. 128 rounds evenly.
+    Threshold(u8),
+    /// 4x4 Bayer matrix. Quick and stable, which suits images that change a little at a time.
+    Ordered,
+    /// Floyd-Steinberg error diffusion. Best for photos, but needs scratch space.
+    FloydSteinberg,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ConvertError {
+    /// The image data is shorter than its size and format call for.
+    ImageSize,
+    /// The output buffers are the wrong size for the image.
+    OutputSize,
+    /// Error diffusion was asked for without [`scratch_len`] entries of scratch space.
+    ScratchSize,
+}
+
+/// Scratch entries [`Dither::FloydSteinberg`] needs for an image `width` pixels across.
+pub const fn scratch_len(width: usize) -> usize {
+    2 * (width + 2)
+}
+
+/// Bytes in one MSB-first 1bpp plane of a `width` by `height` image.
+pub const fn plane_len(width: usize, height: usize) -> usize {
+    width.div_ceil(8) * height
+}
+
+#[derive(Copy, Clone)]
+pub struct Image<'a> {
+    format: PixelFormat,
+    width: usize,
+    height: usize,
+    data: &'a [u8],
+}
+
+impl<'a> Image<'a> {
+    pub fn new(
+        format: PixelFormat,
+        width: usize,
+        height: usize,
+        data: &'a [u8],
+    ) -> Result<Self, ConvertError> {
+        if data.len() < format.row_len(width) * height {
+            return Err(ConvertError::ImageSize);
+        }
+        Ok(Self {
+            format,
+            width,
+            height,
+            data,
+        })
+    }
+
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Brightness of a pixel, 0 for black up to 255 for white.
+    pub fn luma(&self, x: usize, y: usize) -> u8 {
+        let row = &self.data[y * self.format.row_len(self.width)..];
+        match self.format {
+            PixelFormat::Gray8 => row[x],
+            PixelFormat::Rgb565Le => rgb565_luma(u16::from_le_bytes([row[x * 2], row[x * 2 + 1]])),
+            PixelFormat::Rgb565Be => rgb565_luma(u16::from_be_bytes([row[x * 2], row[x * 2 + 1]])),
+            PixelFormat::Mono1Lsb => match row[x / 8] & (1 << (x % 8)) {
+                0 => 255,
+                _ => 0,
+            },
+        }
+    }
+
+    /// Reduces the image to `levels` evenly spaced levels, calling `put` with each pixel's level
+    /// from 0 for black up, one row at a time from the top.
+    pub fn quantise<F>(
+        &self,
+        levels: u8,
+        dither: Dither,
+        scratch: &mut [i16],
+        mut put: F,
+    ) -> Result<(), ConvertError>
+    where
+        F: FnMut(usize, usize, u8),
+    {
+        let quantiser = Quantiser::new(levels);
+        match dither {
+            Dither::Threshold(threshold) => {
+                let bias = 128 - threshold as i32;
+                for y in 0..self.height {
+                    for x in 0..self.width {
+                        put(x, y, quantiser.nearest(self.luma(x, y) as i32 + bias));
+                    }
+                }
+            }
+            Dither::Ordered => {
+                for y in 0..self.height {
+                    for x in 0..self.width {
+                        let bias = quantiser.ordered_bias(x, y);
+                        put(x, y, quantiser.nearest(self.luma(x, y) as i32 + bias));
+                    }
+                }
+            }
+            Dither::FloydSteinberg => {
+                let len = self.width + 2;
+                if scratch.len() < scratch_len(self.width) {
+                    return Err(ConvertError::ScratchSize);
+                }
+                // Errors for the current and next row, in sixteenths, offset by one so the
+                // neighbours of the edge pixels have somewhere to go.
+                let (mut current, mut next) = scratch[..2 * len].split_at_mut(len);
+                current.fill(0);
+                for y in 0..self.height {
+                    next.fill(0);
+                    for x in 0..self.width {
+                        let value =
+                            (self.luma(x, y) as i32 + current[x + 1] as i32 / 16).clamp(0, 255);
+                        let level = quantiser.nearest(value);
+                        put(x, y, level);
+
+                        let error = (value - quantiser.value(level)) as i16;
+                        current[x + 2] += error * 7;
+                        next[x] += error * 3;
+                        next[x + 1] += error * 5;
+                        next[x + 2] += error;
+                    }
+                    core::mem::swap(&mut current, &mut next);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Packs the image into MSB-first 1bpp, with a set bit for black, as DTM1 and DTM2 take it.
+    /// `plane` must be [`plane_len`] bytes.
+    pub fn pack_1bpp(
+        &self,
+        dither: Dither,
+        scratch: &mut [i16],
+        plane: &mut [u8],
+    ) -> Result<(), ConvertError> {
+        if plane.len() != plane_len(self.width, self.height) {
+            return Err(ConvertError::OutputSize);
+        }
+        plane.fill(0);
+        let row_len = self.width.div_ceil(8);
+        self.quantise(2, dither, scratch, |x, y, level| {
+            if level == 0 {
+                plane[y * row_len + x / 8] |= 0x80 >> (x % 8);
+            }
+        })
+    }
+
+    /// Packs the image into the two planes for a grayscale refresh, as
+    /// [`GrayFramebuffer::plane_1`] and [`GrayFramebuffer::plane_2`] would give them. Both must
+    /// be [`plane_len`] bytes.
+    pub fn pack_2bpp(
+        &self,
+        dither: Dither,
+        scratch: &mut [i16],
+        plane_1: &mut [u8],
+        plane_2: &mut [u8],
+    ) -> Result<(), ConvertError> {
+        let len = plane_len(self.width, self.height);
+        if plane_1.len() != len || plane_2.len() != len {
+            return Err(ConvertError::OutputSize);
+        }
+        plane_1.fill(0);
+        plane_2.fill(0);
+        let row_len = self.width.div_ceil(8);
+        self.quantise(4, dither, scratch, |x, y, level| {
+            let level = gray_level(level);
+            let (index, mask) = (y * row_len + x / 8, 0x80 >> (x % 8));
+            if level.plane_1_bit() {
+                plane_1[index] |= mask;
+            }
+            if level.plane_2_bit() {
+                plane_2[index] |= mask;
+            }
+        })
+    }
+}
+
+fn rgb565_luma(pixel: u16) -> u8 {
+    let r = (pixel >> 11) as u32 & 0x1f;
+    let g = (pixel >> 5) as u32 & 0x3f;
+    let b = pixel as u32 & 0x1f;
+    let (r, g, b) = (
+        (r << 3) | (r >> 2),
+        (g << 2) | (g >> 4),
+        (b << 3) | (b >> 2),
+    );
+    ((r * 77 + g * 150 + b * 29) >> 8) as u8
+}
+
+fn gray_level(level: u8) -> GrayLevel {
+    match level {
+        0 => GrayLevel::Black,
+        1 => GrayLevel::DarkGray,
+        2 => GrayLevel::LightGray,
+        _ => GrayLevel::White,
+    }
+}
+
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+struct Quantiser {
+    steps: i32,
+}
+
+impl Quantiser {
+    fn new(levels: u8) -> Self {
+        Self {
+            steps: levels.max(2) as i32 - 1,
+        }
+    }
+
+    /// The nearest level to `value`, which may be out of range.
+    fn nearest(&self, value: i32) -> u8 {
+        ((value.clamp(0, 255) * self.steps + 127) / 255) as u8
+    }
+
+    /// Brightness of `level`.
+    fn value(&self, level: u8) -> i32 {
+        level as i32 * 255 / self.steps
+    }
+
+    /// Offset for pixel `x`, `y` spreading evenly over one step either side of zero.
+    fn ordered_bias(&self, x: usize, y: usize) -> i32 {
+        let step = 255 / self.steps;
+        let threshold = BAYER_4X4[y % 4][x % 4] as i32;
+        (2 * threshold + 1) * step / 32 - step / 2
+    }
+}
+
+impl<const BANKS: usize, const LINES: usize> Framebuffer<BANKS, LINES> {
+    /// Draws `image` in black and white with its top left corner at `x`, `y`, clipping anything
+    /// that falls outside the framebuffer.
+    pub fn draw_image(
+        &mut self,
+        image: &Image,
+        x: usize,
+        y: usize,
+        dither: Dither,
+        scratch: &mut [i16],
+    ) -> Result<(), ConvertError> {
+        image.quantise(2, dither, scratch, |image_x, image_y, level| {
+            let colour = match level {
+                0 => Colour::Black,
+                _ => Colour::White,
+            };
+            self.set_pixel(x + image_x, y + image_y, colour);
+        })
+    }
+}
+
+impl<const BANKS: usize, const LINES: usize> GrayFramebuffer<BANKS, LINES> {
+    /// Draws `image` in four grays with its top left corner at `x`, `y`, clipping anything that
+    /// falls outside the framebuffer.
+    pub fn draw_image(
+        &mut self,
+        image: &Image,
+        x: usize,
+        y: usize,
+        dither: Dither,
+        scratch: &mut [i16],
+    ) -> Result<(), ConvertError> {
+        image.quantise(4, dither, scratch, |image_x, image_y, level| {
+            self.set_pixel(x + image_x, y + image_y, gray_level(level));
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold() {
+        let pixels = [0, 127, 128, 255, 255, 128, 127, 0, 0];
+        let image = Image::new(PixelFormat::Gray8, 9, 1, &pixels).unwrap();
+        let mut plane = [0; 2];
+        image
+            .pack_1bpp(Dither::Threshold(128), &mut [], &mut plane)
+            .unwrap();
+        assert_eq!([0b1100_0011, 0b1000_0000], plane);
+    }
+
+    #[test]
+    fn test_mono_lsb_first() {
+        let image = Image::new(PixelFormat::Mono1Lsb, 8, 1, &[0b0000_0011]).unwrap();
+        let mut plane = [0; 1];
+        image
+            .pack_1bpp(Dither::Threshold(128), &mut [], &mut plane)
+            .unwrap();
+        assert_eq!([0b1100_0000], plane);
+    }
+
+    #[test]
+    fn test_rgb565() {
+        let white = 0xffffu16.to_le_bytes();
+        let red = 0xf800u16.to_le_bytes();
+        let pixels = [white[0], white[1], red[0], red[1]];
+        let image = Image::new(PixelFormat::Rgb565Le, 2, 1, &pixels).unwrap();
+        assert_eq!((255, 76), (image.luma(0, 0), image.luma(1, 0)));
+    }
+
+    #[test]
+    fn test_gray_levels() {
+        let pixels = [0, 85, 170, 255];
+        let image = Image::new(PixelFormat::Gray8, 4, 1, &pixels).unwrap();
+        let (mut plane_1, mut plane_2) = ([0; 1], [0; 1]);
+        image
+            .pack_2bpp(Dither::Threshold(128), &mut [], &mut plane_1, &mut plane_2)
+            .unwrap();
+        // Black, dark gray, light gray, white.
+        assert_eq!(([0b1010_0000], [0b1100_0000]), (plane_1, plane_2));
+    }
+
+    #[test]
+    fn test_floyd_steinberg_keeps_average() {
+        let pixels = [128; 64];
+        let image = Image::new(PixelFormat::Gray8, 8, 8, &pixels).unwrap();
+        let mut scratch = [0; scratch_len(8)];
+        let mut black = 0;
+        image
+            .quantise(2, Dither::FloydSteinberg, &mut scratch, |_, _, level| {
+                black += (level == 0) as u32
+            })
+            .unwrap();
+        assert!((28..=36).contains(&black), "{} black pixels", black);
+        assert_eq!(
+            Err(ConvertError::ScratchSize),
+            image.quantise(2, Dither::FloydSteinberg, &mut [], |_, _, _| {})
+        );
+    }
+}
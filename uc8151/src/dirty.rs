@@ -0,0 +1,139 @@
+//! Tracking of the parts of a framebuffer changed since it was last sent to the panel.
+//!
+//! Every change to a [`Framebuffer`] marks the channel banks and lines it touched. Touching or
+//! overlapping marks are merged as they come in, so a handful of rectangles is enough to cover
+//! what an application typically redraws, and [`Framebuffer::flush`] can then send just those
+//! with partial refreshes.
+
+use embedded_hal::blocking::delay::DelayMs;
+
+use super::framebuffer::Framebuffer;
+use super::interface::{Bus, Input, Output, Readback};
+use super::refresh_policy::RefreshKind;
+use super::region::{Region, RegionError};
+use super::trace::Trace;
+use super::Uc8151;
+
+pub const MAX_DIRTY_REGIONS: usize = 4;
+
+/// Share of the panel, in percent, that has to be dirty before [`Framebuffer::flush`] redraws
+/// everything with one full refresh instead of several partial ones.
+pub const FULL_REFRESH_PERCENT: u32 = 50;
+
+/// Bank-aligned regions in the panel's own coordinates, i.e. ignoring rotation.
+#[derive(Clone)]
+pub struct DirtyRegions {
+    regions: [Option<Region>; MAX_DIRTY_REGIONS],
+}
+
+impl DirtyRegions {
+    pub const fn new() -> Self {
+        Self {
+            regions: [None; MAX_DIRTY_REGIONS],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.iter().all(Option::is_none)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Region> + '_ {
+        self.regions.iter().flatten().copied()
+    }
+
+    /// Number of dirty pixels. The regions never overlap, so this is just their total area.
+    pub fn area(&self) -> u32 {
+        self.iter().map(|region| region.area()).sum()
+    }
+
+    pub fn clear(&mut self) {
+        self.regions = [None; MAX_DIRTY_REGIONS];
+    }
+
+    pub fn add(&mut self, region: Region) {
+        let mut region = region.align_to_banks();
+        if self.iter().any(|dirty| dirty.contains(&region)) {
+            return;
+        }
+
+        // Absorb everything the new region touches. Growing it may make it touch more, so
+        // keep going until nothing changes.
+        while let Some(slot) = self
+            .regions
+            .iter_mut()
+            .find(|slot| matches!(slot, Some(dirty) if touches(dirty, &region)))
+        {
+            region = region.union(&slot.take().unwrap());
+        }
+
+        if let Some(slot) = self.regions.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(region);
+            return;
+        }
+
+        // Out of slots, so merge into whichever region grows the least and start over with
+        // the result, which may now touch others.
+        let (index, _) = self
+            .regions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                slot.map(|dirty| (index, dirty.union(&region).area() - dirty.area()))
+            })
+            .min_by_key(|(_, growth)| *growth)
+            .unwrap();
+        let merged = self.regions[index].take().unwrap().union(&region);
+        self.add(merged);
+    }
+}
+
+impl Default for DirtyRegions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Like [`Region::intersects`], but also true for regions that are side by side.
+fn touches(a: &Region, b: &Region) -> bool {
+    a.x <= b.x + b.width && b.x <= a.x + a.width && a.y <= b.y + b.height && b.y <= a.y + a.height
+}
+
+impl<const BANKS: usize, const LINES: usize> Framebuffer<BANKS, LINES> {
+    /// Sends everything changed since the last flush to the panel and marks it clean.
+    ///
+    /// Each dirty region gets its own partial refresh, unless more than
+    /// [`FULL_REFRESH_PERCENT`] of the panel is dirty, in which case the whole frame is sent with
+    /// a full refresh. Returns `None` if there was nothing to do.
+    pub fn flush<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>(
+        &mut self,
+        display: &mut Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>,
+    ) -> Result<Option<RefreshKind>, RegionError>
+    where
+        SPI: Bus,
+        DC: Output,
+        CS: Output,
+        BUSY: Input,
+        RESET: Output,
+        DELAY: DelayMs<u32>,
+        READBACK: Readback,
+        TRACE: Trace,
+    {
+        if self.dirty().is_empty() {
+            return Ok(None);
+        }
+
+        let panel_area = (BANKS * 8 * LINES) as u32;
+        let kind = if self.dirty().area() * 100 > panel_area * FULL_REFRESH_PERCENT {
+            display.full_refresh(self)?;
+            RefreshKind::Full
+        } else {
+            for region in self.dirty().iter() {
+                display.update_panel_region(region, self)?;
+            }
+            RefreshKind::Partial
+        };
+
+        self.mark_clean();
+        Ok(Some(kind))
+    }
+}
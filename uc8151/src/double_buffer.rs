@@ -0,0 +1,98 @@
+//! Differential updates from a pair of framebuffers.
+//!
+//! The controller treats DTM1 as the frame currently on the panel and DTM2 as the frame to show,
+//! and picks a LUT for each pixel from the pair. [`DoubleBuffered`] keeps the last frame it
+//! showed so it can send both, and loads the [differential](Luts::differential) version of the
+//! panel's LUTs so only pixels that changed get driven.
+
+use embedded_hal::blocking::delay::DelayMs;
+
+use super::framebuffer::Framebuffer;
+use super::interface::{Bus, Input, Output, Readback};
+use super::luts::Luts;
+use super::region::RegionError;
+use super::trace::Trace;
+use super::Uc8151;
+
+pub struct DoubleBuffered<const BANKS: usize, const LINES: usize> {
+    current: Framebuffer<BANKS, LINES>,
+    previous: Framebuffer<BANKS, LINES>,
+    /// Whether `previous` is known to match the panel.
+    shown: bool,
+}
+
+impl<const BANKS: usize, const LINES: usize> DoubleBuffered<BANKS, LINES> {
+    pub const fn new() -> Self {
+        Self {
+            current: Framebuffer::new(),
+            previous: Framebuffer::new(),
+            shown: false,
+        }
+    }
+
+    /// The frame that will be shown next.
+    pub fn framebuffer(&self) -> &Framebuffer<BANKS, LINES> {
+        &self.current
+    }
+
+    pub fn framebuffer_mut(&mut self) -> &mut Framebuffer<BANKS, LINES> {
+        &mut self.current
+    }
+
+    /// The frame that was shown last.
+    pub fn previous(&self) -> &Framebuffer<BANKS, LINES> {
+        &self.previous
+    }
+
+    /// Forgets what is on the panel, so the next [`DoubleBuffered::show`] does a full refresh.
+    /// Use this after anything else has drawn to the panel.
+    pub fn invalidate(&mut self) {
+        self.shown = false;
+    }
+
+    /// Shows the current frame, driving only the pixels that differ from the previous one.
+    ///
+    /// The first call, and the first after [`DoubleBuffered::invalidate`], does a normal full
+    /// refresh since there is no previous frame to compare against. So does every call for a
+    /// panel whose profile has no LUTs of its own. Whatever LUTs were loaded before are loaded
+    /// again afterwards.
+    pub fn show<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>(
+        &mut self,
+        display: &mut Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>,
+    ) -> Result<(), RegionError>
+    where
+        SPI: Bus,
+        DC: Output,
+        CS: Output,
+        BUSY: Input,
+        RESET: Output,
+        DELAY: DelayMs<u32>,
+        READBACK: Readback,
+        TRACE: Trace,
+    {
+        match display.profile().luts.map(Luts::differential) {
+            Some(differential) if self.shown => {
+                display.check_framebuffer(&self.current)?;
+
+                let luts = display.luts().cloned();
+                display.load_luts(&differential);
+                display.data_start_transmission_1_slice(self.previous.as_bytes());
+                display.data_start_transmission_2_slice(self.current.as_bytes());
+                display.data_stop();
+                display.display_refresh(true);
+                display.restore_luts(luts);
+            }
+            _ => display.full_refresh(&self.current)?,
+        }
+
+        self.previous.clone_from(&self.current);
+        self.shown = true;
+        Ok(())
+    }
+}
+
+impl<const BANKS: usize, const LINES: usize> Default for DoubleBuffered<BANKS, LINES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,301 @@
+//! Skipping refreshes that would not change what is on the panel.
+//!
+//! A refresh takes seconds and wears the panel whether or not the image changed, which is
+//! wasteful when e.g. the same name screen is redrawn after waking up. [`FrameCache`] hashes each
+//! frame on its way out and leaves the panel alone if the hash matches the last frame shown. The
+//! last hash lives in a [`HashStore`], so it can be kept somewhere that survives a reset.
+//!
+//! The cache also notes the display's [refresh count](Uc8151::refresh_count) with each hash, so
+//! a refresh it didn't see, from [`Framebuffer::flush`] or anything else, makes it send the next
+//! frame regardless.
+
+use embedded_hal::blocking::delay::DelayMs;
+
+use super::framebuffer::Framebuffer;
+use super::grayscale::GrayFramebuffer;
+use super::interface::{Bus, Input, Output, Readback};
+use super::region::RegionError;
+use super::trace::Trace;
+use super::tri_colour::TriColourFramebuffer;
+use super::Uc8151;
+
+const FNV_OFFSET_BASIS: u32 = 0x811c_9dc5;
+const FNV_PRIME: u32 = 0x0100_0193;
+
+/// 32-bit FNV-1a. Not collision resistant, but cheap and plenty for telling frames apart.
+#[derive(Copy, Clone)]
+pub struct FrameHasher {
+    hash: u32,
+}
+
+impl FrameHasher {
+    pub const fn new() -> Self {
+        Self {
+            hash: FNV_OFFSET_BASIS,
+        }
+    }
+
+    pub fn write<I: IntoIterator<Item = u8>>(&mut self, bytes: I) {
+        for byte in bytes {
+            self.hash = (self.hash ^ byte as u32).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    pub fn finish(&self) -> u32 {
+        self.hash
+    }
+}
+
+impl Default for FrameHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Each kind of frame starts with its own tag, so e.g. a gray frame never matches the
+// black/white frame that happens to share its first plane.
+const TAG_BLACK_WHITE: u8 = 0;
+const TAG_GRAYSCALE: u8 = 1;
+const TAG_TRI_COLOUR: u8 = 2;
+
+pub fn hash<const BANKS: usize, const LINES: usize>(
+    framebuffer: &Framebuffer<BANKS, LINES>,
+) -> u32 {
+    let mut hasher = FrameHasher::new();
+    hasher.write([TAG_BLACK_WHITE]);
+    hasher.write(framebuffer.as_bytes().iter().copied());
+    hasher.finish()
+}
+
+pub fn hash_grayscale<const BANKS: usize, const LINES: usize>(
+    framebuffer: &GrayFramebuffer<BANKS, LINES>,
+) -> u32 {
+    let mut hasher = FrameHasher::new();
+    hasher.write([TAG_GRAYSCALE]);
+    hasher.write(framebuffer.plane_1());
+    hasher.write(framebuffer.plane_2());
+    hasher.finish()
+}
+
+pub fn hash_tri_colour<const BANKS: usize, const LINES: usize>(
+    framebuffer: &TriColourFramebuffer<BANKS, LINES>,
+) -> u32 {
+    let mut hasher = FrameHasher::new();
+    hasher.write([TAG_TRI_COLOUR]);
+    hasher.write(framebuffer.black_plane());
+    hasher.write(framebuffer.red_plane());
+    hasher.finish()
+}
+
+/// Somewhere to keep the hash of the frame on the panel.
+pub trait HashStore {
+    /// The stored hash, or `None` if nothing has been stored or it has been lost.
+    fn load(&mut self) -> Option<u32>;
+    fn store(&mut self, hash: u32);
+    fn clear(&mut self);
+}
+
+/// Keeps the hash in RAM, so it is forgotten on reset.
+#[derive(Default)]
+pub struct RamHashStore {
+    hash: Option<u32>,
+}
+
+impl RamHashStore {
+    pub const fn new() -> Self {
+        Self { hash: None }
+    }
+}
+
+impl HashStore for RamHashStore {
+    fn load(&mut self) -> Option<u32> {
+        self.hash
+    }
+
+    fn store(&mut self, hash: u32) {
+        self.hash = Some(hash);
+    }
+
+    fn clear(&mut self) {
+        self.hash = None;
+    }
+}
+
+pub struct FrameCache<S: HashStore> {
+    store: S,
+    /// The display's refresh count when the stored hash was recorded.
+    refresh_count: u32,
+}
+
+impl<S: HashStore> FrameCache<S> {
+    /// A cache for a display that has yet to refresh, such as one just set up after a reset,
+    /// trusting whatever hash `store` already holds.
+    pub const fn new(store: S) -> Self {
+        Self {
+            store,
+            refresh_count: 0,
+        }
+    }
+
+    pub fn store(&mut self) -> &mut S {
+        &mut self.store
+    }
+
+    /// Forgets what is on the panel, so the next frame is always sent. Use this after anything
+    /// else has drawn to the panel.
+    pub fn invalidate(&mut self) {
+        self.store.clear();
+    }
+
+    /// Whether a frame hashing to `hash` is already on the panel, i.e. it was the last one
+    /// recorded and `display` hasn't refreshed since.
+    pub fn is_shown<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>(
+        &mut self,
+        display: &Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>,
+        hash: u32,
+    ) -> bool
+    where
+        SPI: Bus,
+        DC: Output,
+        CS: Output,
+        BUSY: Input,
+        RESET: Output,
+        DELAY: DelayMs<u32>,
+        READBACK: Readback,
+        TRACE: Trace,
+    {
+        self.refresh_count == display.refresh_count() && self.store.load() == Some(hash)
+    }
+
+    /// Records that `display` has just been refreshed some other way with a frame hashing to
+    /// `hash`.
+    pub fn record<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>(
+        &mut self,
+        display: &Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>,
+        hash: u32,
+    ) where
+        SPI: Bus,
+        DC: Output,
+        CS: Output,
+        BUSY: Input,
+        RESET: Output,
+        DELAY: DelayMs<u32>,
+        READBACK: Readback,
+        TRACE: Trace,
+    {
+        self.store.store(hash);
+        self.refresh_count = display.refresh_count();
+    }
+
+    /// Like [`Uc8151::full_refresh`], but does nothing if `framebuffer` is already on the panel.
+    /// Returns whether the panel was refreshed.
+    pub fn full_refresh<
+        SPI,
+        DC,
+        CS,
+        BUSY,
+        RESET,
+        DELAY,
+        READBACK,
+        TRACE,
+        const BANKS: usize,
+        const LINES: usize,
+    >(
+        &mut self,
+        display: &mut Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>,
+        framebuffer: &Framebuffer<BANKS, LINES>,
+    ) -> Result<bool, RegionError>
+    where
+        SPI: Bus,
+        DC: Output,
+        CS: Output,
+        BUSY: Input,
+        RESET: Output,
+        DELAY: DelayMs<u32>,
+        READBACK: Readback,
+        TRACE: Trace,
+    {
+        let hash = hash(framebuffer);
+        if self.is_shown(display, hash) {
+            return Ok(false);
+        }
+        display.full_refresh(framebuffer)?;
+        self.record(display, hash);
+        Ok(true)
+    }
+
+    /// Like [`Uc8151::display_grayscale`], but does nothing if `framebuffer` is already on the
+    /// panel. Returns whether the panel was refreshed.
+    pub fn display_grayscale<
+        SPI,
+        DC,
+        CS,
+        BUSY,
+        RESET,
+        DELAY,
+        READBACK,
+        TRACE,
+        const BANKS: usize,
+        const LINES: usize,
+    >(
+        &mut self,
+        display: &mut Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>,
+        framebuffer: &GrayFramebuffer<BANKS, LINES>,
+    ) -> Result<bool, RegionError>
+    where
+        SPI: Bus,
+        DC: Output,
+        CS: Output,
+        BUSY: Input,
+        RESET: Output,
+        DELAY: DelayMs<u32>,
+        READBACK: Readback,
+        TRACE: Trace,
+    {
+        let hash = hash_grayscale(framebuffer);
+        if self.is_shown(display, hash) {
+            return Ok(false);
+        }
+        display.display_grayscale(framebuffer)?;
+        self.record(display, hash);
+        Ok(true)
+    }
+
+    /// Like [`Uc8151::display_tri_colour`], but does nothing if `framebuffer` is already on the
+    /// panel. Returns whether the panel was refreshed.
+    pub fn display_tri_colour<
+        SPI,
+        DC,
+        CS,
+        BUSY,
+        RESET,
+        DELAY,
+        READBACK,
+        TRACE,
+        const BANKS: usize,
+        const LINES: usize,
+    >(
+        &mut self,
+        display: &mut Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>,
+        framebuffer: &TriColourFramebuffer<BANKS, LINES>,
+        blocking: bool,
+    ) -> Result<bool, RegionError>
+    where
+        SPI: Bus,
+        DC: Output,
+        CS: Output,
+        BUSY: Input,
+        RESET: Output,
+        DELAY: DelayMs<u32>,
+        READBACK: Readback,
+        TRACE: Trace,
+    {
+        let hash = hash_tri_colour(framebuffer);
+        if self.is_shown(display, hash) {
+            return Ok(false);
+        }
+        display.display_tri_colour(framebuffer, blocking)?;
+        self.record(display, hash);
+        Ok(true)
+    }
+}
@@ -0,0 +1,213 @@
+//! A 1bpp image buffer in the controller's own byte order.
+//!
+//! Framebuffers are sized in 8-pixel channel banks and gate lines rather than in pixels. Stable
+//! Rust can't yet size an array with an expression of a const parameter, such as
+//! `[[u8; WIDTH.div_ceil(8)]; HEIGHT]`, so taking the width in pixels would mean storing the
+//! buffer on the heap or behind an extra parameter. With a concrete panel the expression is
+//! fine, so the type for a profile can still be written in pixels:
+//! `Framebuffer<{ PANEL.width.div_ceil(8) as usize }, { PANEL.height as usize }>`.
+
+use super::dirty::DirtyRegions;
+use super::region::Region;
+use super::rotation::Rotation;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Colour {
+    White,
+    Black,
+}
+
+/// A 1bpp image laid out exactly as the controller expects it.
+///
+/// The size is given in 8-pixel channel banks and gate lines, so a 128x296 panel is a
+/// `Framebuffer<16, 296>`. Each line is `BANKS` bytes, packed MSB-first with a set bit for black,
+/// and lines follow each other in gate order.
+#[derive(Clone)]
+pub struct Framebuffer<const BANKS: usize, const LINES: usize> {
+    buffer: [[u8; BANKS]; LINES],
+    rotation: Rotation,
+    dirty: DirtyRegions,
+}
+
+impl<const BANKS: usize, const LINES: usize> Framebuffer<BANKS, LINES> {
+    pub const fn new() -> Self {
+        Self {
+            buffer: [[0; BANKS]; LINES],
+            rotation: Rotation::Deg0,
+            dirty: DirtyRegions::new(),
+        }
+    }
+
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Sets the rotation used to map pixel coordinates onto the panel. This does not move what
+    /// has already been drawn.
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+    }
+
+    /// Width in the current rotation.
+    pub fn width(&self) -> usize {
+        self.rotation.size(BANKS * 8, LINES).0
+    }
+
+    /// Height in the current rotation.
+    pub fn height(&self) -> usize {
+        self.rotation.size(BANKS * 8, LINES).1
+    }
+
+    fn locate(&self, x: usize, y: usize) -> Option<(usize, usize, u8)> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+        let (x, y) = self.rotation.to_panel(x, y, BANKS * 8);
+        Some((y, x / 8, 0x80 >> (x % 8)))
+    }
+
+    pub fn fill(&mut self, colour: Colour) {
+        let fill = match colour {
+            Colour::White => 0x00,
+            Colour::Black => 0xff,
+        };
+        for line in self.buffer.iter_mut() {
+            line.fill(fill);
+        }
+        self.dirty
+            .add(Region::new(0, 0, (BANKS * 8) as u16, LINES as u16));
+    }
+
+    /// Sets a pixel, ignoring coordinates outside the framebuffer.
+    pub fn set_pixel(&mut self, x: usize, y: usize, colour: Colour) {
+        if let Some(region) = self.write_pixel(x, y, colour) {
+            self.dirty.add(region);
+        }
+    }
+
+    /// Sets each of `pixels` like [`Framebuffer::set_pixel`], but marks them dirty in one go,
+    /// as the smallest region covering them all.
+    pub fn set_pixels(&mut self, pixels: impl IntoIterator<Item = (usize, usize, Colour)>) {
+        let mut touched: Option<Region> = None;
+        for (x, y, colour) in pixels {
+            if let Some(region) = self.write_pixel(x, y, colour) {
+                touched = Some(touched.map_or(region, |touched| touched.union(&region)));
+            }
+        }
+        if let Some(region) = touched {
+            self.dirty.add(region);
+        }
+    }
+
+    /// Sets a pixel without marking it dirty, returning the bank of the line it is in, in the
+    /// panel's own coordinates.
+    pub(crate) fn write_pixel(&mut self, x: usize, y: usize, colour: Colour) -> Option<Region> {
+        let (line, bank, mask) = self.locate(x, y)?;
+        match colour {
+            Colour::White => self.buffer[line][bank] &= !mask,
+            Colour::Black => self.buffer[line][bank] |= mask,
+        }
+        Some(Region::new((bank * 8) as u16, line as u16, 8, 1))
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> Option<Colour> {
+        let (line, bank, mask) = self.locate(x, y)?;
+        match self.buffer[line][bank] & mask {
+            0 => Some(Colour::White),
+            _ => Some(Colour::Black),
+        }
+    }
+
+    /// Copies all of `source` with its top left corner at `x`, `y`, clipping anything that
+    /// falls outside this framebuffer.
+    pub fn blit<const SOURCE_BANKS: usize, const SOURCE_LINES: usize>(
+        &mut self,
+        source: &Framebuffer<SOURCE_BANKS, SOURCE_LINES>,
+        x: usize,
+        y: usize,
+    ) {
+        let pixels = (0..source.height()).flat_map(|source_y| {
+            (0..source.width()).filter_map(move |source_x| {
+                let colour = source.get_pixel(source_x, source_y)?;
+                Some((x + source_x, y + source_y, colour))
+            })
+        });
+        self.set_pixels(pixels);
+    }
+
+    /// What has changed since the framebuffer was last flushed or marked clean.
+    pub fn dirty(&self) -> &DirtyRegions {
+        &self.dirty
+    }
+
+    /// Forgets about any changes, e.g. after sending the whole frame some other way.
+    pub fn mark_clean(&mut self) {
+        self.dirty.clear();
+    }
+
+    /// The whole frame, ready to send to DTM1 or DTM2.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.buffer.as_flattened()
+    }
+
+    /// The bytes for banks `first_bank..=last_bank` of `line`.
+    pub fn line_bytes(&self, line: usize, first_bank: usize, last_bank: usize) -> &[u8] {
+        &self.buffer[line][first_bank..=last_bank]
+    }
+}
+
+impl<const BANKS: usize, const LINES: usize> Default for Framebuffer<BANKS, LINES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout() {
+        let mut framebuffer = Framebuffer::<2, 3>::new();
+        framebuffer.set_pixel(0, 0, Colour::Black);
+        framebuffer.set_pixel(9, 2, Colour::Black);
+        assert_eq!(&[0x80, 0, 0, 0, 0, 0x40], framebuffer.as_bytes());
+        assert_eq!(Some(Colour::Black), framebuffer.get_pixel(9, 2));
+        assert_eq!(None, framebuffer.get_pixel(16, 0));
+    }
+
+    #[test]
+    fn test_quarter_turn() {
+        let mut framebuffer = Framebuffer::<2, 3>::new();
+        framebuffer.set_rotation(Rotation::Deg90);
+        assert_eq!((3, 16), (framebuffer.width(), framebuffer.height()));
+        // The top left corner of the rotated image is the top right corner of the panel.
+        framebuffer.set_pixel(0, 0, Colour::Black);
+        assert_eq!(0x01, framebuffer.line_bytes(0, 1, 1)[0]);
+    }
+
+    #[test]
+    fn test_dirty() {
+        let mut framebuffer = Framebuffer::<4, 8>::new();
+        assert!(framebuffer.dirty().is_empty());
+        framebuffer.set_pixel(10, 3, Colour::Black);
+        framebuffer.set_pixel(11, 4, Colour::Black);
+        let dirty: Vec<_> = framebuffer.dirty().iter().collect();
+        assert_eq!(vec![Region::new(8, 3, 8, 2)], dirty);
+        framebuffer.mark_clean();
+        assert!(framebuffer.dirty().is_empty());
+    }
+
+    #[test]
+    fn test_blit_dirty() {
+        let mut source = Framebuffer::<1, 2>::new();
+        source.fill(Colour::Black);
+        let mut framebuffer = Framebuffer::<4, 8>::new();
+        framebuffer.blit(&source, 12, 5);
+        // Clipped at the bottom, and widened to whole banks.
+        let dirty: Vec<_> = framebuffer.dirty().iter().collect();
+        assert_eq!(vec![Region::new(8, 5, 16, 2)], dirty);
+        assert_eq!(Some(Colour::Black), framebuffer.get_pixel(19, 6));
+        assert_eq!(Some(Colour::White), framebuffer.get_pixel(20, 6));
+    }
+}
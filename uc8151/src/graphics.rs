@@ -0,0 +1,222 @@
+//! `embedded-graphics` support for the framebuffers.
+//!
+//! [`Framebuffer`] draws with `BinaryColor`, where `On` is black. [`GrayFramebuffer`] draws with
+//! `Gray2` and [`TriColourFramebuffer`] with [`TriColour`]. All of them report their size in the
+//! current rotation. A [`Band`] draws with whichever of those matches what is being rendered,
+//! and reports the size of the whole image.
+
+use core::convert::Infallible;
+
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{OriginDimensions, Size};
+use embedded_graphics_core::pixelcolor::raw::RawU2;
+use embedded_graphics_core::pixelcolor::{BinaryColor, Gray2, GrayColor, PixelColor};
+use embedded_graphics_core::Pixel;
+
+use super::banded::Band;
+use super::framebuffer::{Colour, Framebuffer};
+use super::grayscale::{GrayFramebuffer, GrayLevel};
+use super::tri_colour::{TriColour, TriColourFramebuffer};
+
+impl From<BinaryColor> for Colour {
+    fn from(colour: BinaryColor) -> Self {
+        match colour {
+            BinaryColor::Off => Colour::White,
+            BinaryColor::On => Colour::Black,
+        }
+    }
+}
+
+impl From<Colour> for BinaryColor {
+    fn from(colour: Colour) -> Self {
+        match colour {
+            Colour::White => BinaryColor::Off,
+            Colour::Black => BinaryColor::On,
+        }
+    }
+}
+
+impl From<Gray2> for GrayLevel {
+    fn from(colour: Gray2) -> Self {
+        match colour.luma() {
+            0 => GrayLevel::Black,
+            1 => GrayLevel::DarkGray,
+            2 => GrayLevel::LightGray,
+            _ => GrayLevel::White,
+        }
+    }
+}
+
+impl From<GrayLevel> for Gray2 {
+    fn from(level: GrayLevel) -> Self {
+        match level {
+            GrayLevel::Black => Gray2::new(0),
+            GrayLevel::DarkGray => Gray2::new(1),
+            GrayLevel::LightGray => Gray2::new(2),
+            GrayLevel::White => Gray2::new(3),
+        }
+    }
+}
+
+impl PixelColor for TriColour {
+    type Raw = RawU2;
+}
+
+impl From<BinaryColor> for TriColour {
+    fn from(colour: BinaryColor) -> Self {
+        match colour {
+            BinaryColor::Off => TriColour::White,
+            BinaryColor::On => TriColour::Black,
+        }
+    }
+}
+
+fn point(pixel: &Pixel<impl PixelColor>) -> Option<(usize, usize)> {
+    let Pixel(point, _) = pixel;
+    if point.x < 0 || point.y < 0 {
+        return None;
+    }
+    Some((point.x as usize, point.y as usize))
+}
+
+impl<const BANKS: usize, const LINES: usize> DrawTarget for Framebuffer<BANKS, LINES> {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.set_pixels(pixels.into_iter().filter_map(|pixel| {
+            let (x, y) = point(&pixel)?;
+            Some((x, y, pixel.1.into()))
+        }));
+        Ok(())
+    }
+
+    fn clear(&mut self, colour: Self::Color) -> Result<(), Self::Error> {
+        self.fill(colour.into());
+        Ok(())
+    }
+}
+
+impl<const BANKS: usize, const LINES: usize> OriginDimensions for Framebuffer<BANKS, LINES> {
+    fn size(&self) -> Size {
+        Size::new(self.width() as u32, self.height() as u32)
+    }
+}
+
+impl<const BANKS: usize, const LINES: usize> DrawTarget for GrayFramebuffer<BANKS, LINES> {
+    type Color = Gray2;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            if let Some((x, y)) = point(&pixel) {
+                self.set_pixel(x, y, pixel.1.into());
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, colour: Self::Color) -> Result<(), Self::Error> {
+        self.fill(colour.into());
+        Ok(())
+    }
+}
+
+impl<const BANKS: usize, const LINES: usize> OriginDimensions for GrayFramebuffer<BANKS, LINES> {
+    fn size(&self) -> Size {
+        Size::new(self.width() as u32, self.height() as u32)
+    }
+}
+
+impl<const BANKS: usize, const LINES: usize> DrawTarget for TriColourFramebuffer<BANKS, LINES> {
+    type Color = TriColour;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            if let Some((x, y)) = point(&pixel) {
+                self.set_pixel(x, y, pixel.1);
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, colour: Self::Color) -> Result<(), Self::Error> {
+        self.fill(colour);
+        Ok(())
+    }
+}
+
+impl<const BANKS: usize, const LINES: usize> OriginDimensions
+    for TriColourFramebuffer<BANKS, LINES>
+{
+    fn size(&self) -> Size {
+        Size::new(self.width() as u32, self.height() as u32)
+    }
+}
+
+impl<'a, const BANKS: usize, const LINES: usize> DrawTarget for Band<'a, Colour, BANKS, LINES> {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            if let Some((x, y)) = point(&pixel) {
+                self.set_pixel(x, y, pixel.1.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, const BANKS: usize, const LINES: usize> DrawTarget for Band<'a, GrayLevel, BANKS, LINES> {
+    type Color = Gray2;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            if let Some((x, y)) = point(&pixel) {
+                self.set_pixel(x, y, pixel.1.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, const BANKS: usize, const LINES: usize> DrawTarget for Band<'a, TriColour, BANKS, LINES> {
+    type Color = TriColour;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            if let Some((x, y)) = point(&pixel) {
+                self.set_pixel(x, y, pixel.1);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, C, const BANKS: usize, const LINES: usize> OriginDimensions for Band<'a, C, BANKS, LINES> {
+    fn size(&self) -> Size {
+        Size::new(self.width() as u32, self.height() as u32)
+    }
+}
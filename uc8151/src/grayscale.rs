@@ -0,0 +1,196 @@
+//! 4-level grayscale using both data transmission bit-planes.
+//!
+//! Each pixel is stored as two bits, one in the DTM1 ("old") plane and one in the DTM2 ("new")
+//! plane. The controller picks one of the W2W/B2W/W2B/B2B LUTs for every pixel from that pair,
+//! so loading a different waveform into each of the four LUTs gives four gray levels.
+//!
+//! | Level       | DTM1 | DTM2 | LUT |
+//! |-------------|------|------|-----|
+//! | `White`     | 0    | 0    | W2W |
+//! | `LightGray` | 1    | 0    | B2W |
+//! | `DarkGray`  | 0    | 1    | W2B |
+//! | `Black`     | 1    | 1    | B2B |
+
+use super::framebuffer::{Colour, Framebuffer};
+use super::register::*;
+use super::rotation::Rotation;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GrayLevel {
+    White = 0b00,
+    LightGray = 0b01,
+    DarkGray = 0b10,
+    Black = 0b11,
+}
+
+impl GrayLevel {
+    fn from_bits(plane_1: bool, plane_2: bool) -> Self {
+        match (plane_1, plane_2) {
+            (false, false) => Self::White,
+            (true, false) => Self::LightGray,
+            (false, true) => Self::DarkGray,
+            (true, true) => Self::Black,
+        }
+    }
+
+    pub(crate) fn plane_1_bit(self) -> bool {
+        self as u8 & 0b01 != 0
+    }
+
+    pub(crate) fn plane_2_bit(self) -> bool {
+        self as u8 & 0b10 != 0
+    }
+
+    /// The waveform that drives a pixel to this level.
+    ///
+    /// Every level shares the same frame timing so the four LUTs stay in step: a shake to loosen
+    /// the previous image, a long drive to white, then four equal slots that each push the pixel
+    /// a step darker.
+    pub fn lut(self) -> LutSettingGroup {
+        use LevelSelection::*;
+
+        let darken = match self {
+            Self::White => [Gnd, Gnd, Gnd, Gnd],
+            Self::LightGray => [Vdh, Gnd, Gnd, Gnd],
+            Self::DarkGray => [Vdh, Vdh, Gnd, Gnd],
+            Self::Black => [Vdh, Vdh, Vdh, Vdh],
+        };
+        lut_group([Vdh, Vdl, Gnd, Gnd], [Vdl, Vdl, Vdl, Gnd], darken)
+    }
+}
+
+/// VCOM waveform to go with [`GrayLevel::lut`], holding VCOM at VCOM_DC for every phase.
+pub fn vcom_lut() -> LutSettingGroup {
+    use LevelSelection::*;
+
+    lut_group([Gnd; 4], [Gnd; 4], [Gnd; 4])
+}
+
+fn lut_group(
+    shake: [LevelSelection; 4],
+    clear: [LevelSelection; 4],
+    darken: [LevelSelection; 4],
+) -> LutSettingGroup {
+    LutSettingGroup([
+        LutSetting {
+            level_select_1: shake[0],
+            level_select_2: shake[1],
+            level_select_3: shake[2],
+            level_select_4: shake[3],
+            number_of_frames_1: 4,
+            number_of_frames_2: 4,
+            number_of_frames_3: 0,
+            number_of_frames_4: 0,
+            times_to_repeat: 2,
+        },
+        LutSetting {
+            level_select_1: clear[0],
+            level_select_2: clear[1],
+            level_select_3: clear[2],
+            level_select_4: clear[3],
+            number_of_frames_1: 8,
+            number_of_frames_2: 8,
+            number_of_frames_3: 8,
+            number_of_frames_4: 0,
+            times_to_repeat: 1,
+        },
+        LutSetting {
+            level_select_1: darken[0],
+            level_select_2: darken[1],
+            level_select_3: darken[2],
+            level_select_4: darken[3],
+            number_of_frames_1: 4,
+            number_of_frames_2: 4,
+            number_of_frames_3: 4,
+            number_of_frames_4: 4,
+            times_to_repeat: 1,
+        },
+        Default::default(),
+        Default::default(),
+        Default::default(),
+        Default::default(),
+    ])
+}
+
+/// A 2bpp image stored as the two bit-planes the controller expects.
+///
+/// The size is given in 8-pixel channel banks and gate lines, as for [`Framebuffer`].
+#[derive(Clone)]
+pub struct GrayFramebuffer<const BANKS: usize, const LINES: usize> {
+    plane_1: Framebuffer<BANKS, LINES>,
+    plane_2: Framebuffer<BANKS, LINES>,
+}
+
+impl<const BANKS: usize, const LINES: usize> GrayFramebuffer<BANKS, LINES> {
+    pub const fn new() -> Self {
+        Self {
+            plane_1: Framebuffer::new(),
+            plane_2: Framebuffer::new(),
+        }
+    }
+
+    pub fn rotation(&self) -> Rotation {
+        self.plane_1.rotation()
+    }
+
+    /// Sets the rotation used to map pixel coordinates onto the panel. This does not move what
+    /// has already been drawn.
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.plane_1.set_rotation(rotation);
+        self.plane_2.set_rotation(rotation);
+    }
+
+    /// Width in the current rotation.
+    pub fn width(&self) -> usize {
+        self.plane_1.width()
+    }
+
+    /// Height in the current rotation.
+    pub fn height(&self) -> usize {
+        self.plane_1.height()
+    }
+
+    pub fn fill(&mut self, level: GrayLevel) {
+        self.plane_1.fill(bit_colour(level.plane_1_bit()));
+        self.plane_2.fill(bit_colour(level.plane_2_bit()));
+    }
+
+    /// Sets a pixel, ignoring coordinates outside the framebuffer.
+    pub fn set_pixel(&mut self, x: usize, y: usize, level: GrayLevel) {
+        // Nothing reads the planes' dirty regions, so don't spend time keeping them.
+        self.plane_1
+            .write_pixel(x, y, bit_colour(level.plane_1_bit()));
+        self.plane_2
+            .write_pixel(x, y, bit_colour(level.plane_2_bit()));
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> Option<GrayLevel> {
+        Some(GrayLevel::from_bits(
+            self.plane_1.get_pixel(x, y)? == Colour::Black,
+            self.plane_2.get_pixel(x, y)? == Colour::Black,
+        ))
+    }
+
+    /// Bytes for DTM1.
+    pub fn plane_1(&self) -> impl Iterator<Item = u8> + '_ {
+        self.plane_1.as_bytes().iter().copied()
+    }
+
+    /// Bytes for DTM2.
+    pub fn plane_2(&self) -> impl Iterator<Item = u8> + '_ {
+        self.plane_2.as_bytes().iter().copied()
+    }
+}
+
+impl<const BANKS: usize, const LINES: usize> Default for GrayFramebuffer<BANKS, LINES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn bit_colour(bit: bool) -> Colour {
+    match bit {
+        false => Colour::White,
+        true => Colour::Black,
+    }
+}
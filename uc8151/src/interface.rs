@@ -0,0 +1,123 @@
+//! The hardware the driver talks to the controller through.
+//!
+//! Anything implementing the usual embedded-hal traits works: an SPI peripheral for [`Bus`],
+//! GPIO pins for [`Output`] and [`Input`], and a delay. The traits here only exist so the driver
+//! can unwrap hardware errors without every user of it spelling out that they are `Debug`.
+//!
+//! The one thing embedded-hal has no trait for is [`Readback`], since the controller answers on
+//! the same line it listens on.
+
+use core::fmt::Debug;
+
+use embedded_hal::blocking::spi::{Write, WriteIter};
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+use embedded_hal::spi::FullDuplex;
+
+/// Bytes [`Bus::write_fifo`] lets run ahead of what has come back. This is the depth of the
+/// RP2040 SPI FIFOs, and must be no deeper than the receive FIFO of the peripheral in use.
+pub const SPI_FIFO_DEPTH: usize = 8;
+
+pub trait Bus {
+    fn write(&mut self, bytes: &[u8]);
+    fn write_iter<I: IntoIterator<Item = u8>>(&mut self, bytes: I);
+    /// Writes `bytes` keeping the transmit FIFO topped up, rather than waiting for each byte to
+    /// come back before sending the next like the blocking `write` does.
+    fn write_fifo(&mut self, bytes: &[u8]);
+}
+
+impl<S> Bus for S
+where
+    S: Write<u8> + WriteIter<u8> + FullDuplex<u8>,
+    <S as Write<u8>>::Error: Debug,
+    <S as WriteIter<u8>>::Error: Debug,
+{
+    fn write(&mut self, bytes: &[u8]) {
+        Write::write(self, bytes).unwrap();
+    }
+
+    fn write_iter<I: IntoIterator<Item = u8>>(&mut self, bytes: I) {
+        WriteIter::write_iter(self, bytes).unwrap();
+    }
+
+    fn write_fifo(&mut self, bytes: &[u8]) {
+        // Anything left over from an earlier transfer isn't ours to count.
+        while self.read().is_ok() {}
+
+        let mut in_flight = 0;
+        let mut bytes = bytes.iter().copied().peekable();
+        while let Some(&byte) = bytes.peek() {
+            // Everything sent comes back in the receive FIFO, which must not overflow.
+            while in_flight > 0 && self.read().is_ok() {
+                in_flight -= 1;
+            }
+            if in_flight < SPI_FIFO_DEPTH && self.send(byte).is_ok() {
+                in_flight += 1;
+                bytes.next();
+            }
+        }
+        while in_flight > 0 {
+            if self.read().is_ok() {
+                in_flight -= 1;
+            }
+        }
+    }
+}
+
+pub trait Output {
+    fn set_level(&mut self, high: bool);
+}
+
+impl<P> Output for P
+where
+    P: OutputPin,
+    P::Error: Debug,
+{
+    fn set_level(&mut self, high: bool) {
+        match high {
+            true => self.set_high().unwrap(),
+            false => self.set_low().unwrap(),
+        }
+    }
+}
+
+pub trait Input {
+    fn level(&mut self) -> bool;
+}
+
+impl<P> Input for P
+where
+    P: InputPin,
+    P::Error: Debug,
+{
+    fn level(&mut self) -> bool {
+        self.is_high().unwrap()
+    }
+}
+
+/// Bit-banged reads over the SPI clock and data lines.
+///
+/// The controller answers on the same data line it listens on, which SPI peripherals can't
+/// read, so the driver takes both lines over as GPIO while it clocks the answer in.
+pub trait Readback {
+    /// Hands the lines back to the SPI peripheral. Called before every write.
+    fn release(&mut self);
+    /// Takes the lines over as GPIO, with the clock as an output and data as an input.
+    fn acquire(&mut self);
+    fn set_clock(&mut self, high: bool);
+    fn data(&mut self) -> bool;
+}
+
+/// For when the lines can't be taken over. Every read comes back as zeroes.
+pub struct NoReadback;
+
+impl Readback for NoReadback {
+    fn release(&mut self) {}
+
+    fn acquire(&mut self) {}
+
+    fn set_clock(&mut self, _high: bool) {}
+
+    fn data(&mut self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,941 @@
+//! Driver for the UltraChip UC8151 e-paper controller, as used in the Pimoroni Badger 2040.
+//!
+//! The driver talks to the controller through embedded-hal traits, see [`interface`], so it runs
+//! on anything with SPI and a few GPIO pins. Everything that deals with images, from the
+//! framebuffers to the refresh strategies, is plain `no_std` code that builds and tests on the
+//! host as well.
+
+#![cfg_attr(not(test), no_std)]
+// Register values keep their bit fields grouped.
+#![allow(clippy::unusual_byte_groupings, clippy::derivable_impls)]
+
+use embedded_hal::blocking::delay::DelayMs;
+
+pub mod banded;
+pub mod convert;
+pub mod dirty;
+pub mod double_buffer;
+pub mod frame_hash;
+pub mod framebuffer;
+pub mod graphics;
+pub mod grayscale;
+pub mod interface;
+pub mod luts;
+pub mod profile;
+pub mod refresh_policy;
+pub mod region;
+pub mod register;
+pub mod rotation;
+pub mod trace;
+pub mod tri_colour;
+
+use self::framebuffer::Framebuffer;
+use self::grayscale::{GrayFramebuffer, GrayLevel};
+use self::interface::{Bus, Input, Output, Readback};
+use self::luts::Luts;
+use self::profile::PanelProfile;
+use self::region::{Region, RegionError};
+use self::register::*;
+use self::rotation::Rotation;
+use self::trace::{Event, NoTrace, Trace};
+use self::tri_colour::TriColourFramebuffer;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub struct Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE = NoTrace>
+where
+    SPI: Bus,
+    DC: Output,
+    CS: Output,
+    BUSY: Input,
+    RESET: Output,
+    DELAY: DelayMs<u32>,
+    READBACK: Readback,
+    TRACE: Trace,
+{
+    spi: SPI,
+    dc_pin: DC,
+    cs_pin: CS,
+    busy_pin: BUSY,
+    reset_pin: RESET,
+    delay: DELAY,
+    readback: READBACK,
+    trace: TRACE,
+    profile: PanelProfile,
+    colour_selection: ColourSelection,
+    rotation: Rotation,
+    /// The LUTs last loaded into the registers, if any.
+    luts: Option<Luts>,
+    refresh_count: u32,
+}
+
+impl<SPI, DC, CS, BUSY, RESET, DELAY, READBACK> Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK>
+where
+    SPI: Bus,
+    DC: Output,
+    CS: Output,
+    BUSY: Input,
+    RESET: Output,
+    DELAY: DelayMs<u32>,
+    READBACK: Readback,
+{
+    /// `busy_pin` should be pulled up, as the controller only ever pulls it low.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        spi: SPI,
+        dc_pin: DC,
+        cs_pin: CS,
+        busy_pin: BUSY,
+        reset_pin: RESET,
+        delay: DELAY,
+        readback: READBACK,
+        profile: PanelProfile,
+    ) -> Self {
+        Self {
+            spi,
+            dc_pin,
+            cs_pin,
+            busy_pin,
+            reset_pin,
+            delay,
+            readback,
+            trace: NoTrace,
+            colour_selection: profile.colour,
+            profile,
+            rotation: Rotation::Deg0,
+            luts: None,
+            refresh_count: 0,
+        }
+    }
+
+    /// Reports everything sent to and read from the controller to `trace` from now on.
+    pub fn with_trace<TRACE: Trace>(
+        self,
+        trace: TRACE,
+    ) -> Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE> {
+        Uc8151 {
+            spi: self.spi,
+            dc_pin: self.dc_pin,
+            cs_pin: self.cs_pin,
+            busy_pin: self.busy_pin,
+            reset_pin: self.reset_pin,
+            delay: self.delay,
+            readback: self.readback,
+            trace,
+            profile: self.profile,
+            colour_selection: self.colour_selection,
+            rotation: self.rotation,
+            luts: self.luts,
+            refresh_count: self.refresh_count,
+        }
+    }
+}
+
+impl<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>
+    Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>
+where
+    SPI: Bus,
+    DC: Output,
+    CS: Output,
+    BUSY: Input,
+    RESET: Output,
+    DELAY: DelayMs<u32>,
+    READBACK: Readback,
+    TRACE: Trace,
+{
+    pub fn trace(&self) -> &TRACE {
+        &self.trace
+    }
+
+    pub fn trace_mut(&mut self) -> &mut TRACE {
+        &mut self.trace
+    }
+
+    /// Selects black/white or black/white/red mode. Takes effect on the next [`Uc8151::setup`].
+    pub fn set_colour_selection(&mut self, colour_selection: ColourSelection) {
+        self.colour_selection = colour_selection;
+    }
+
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Changes the scan directions for `rotation` straight away. Framebuffers need the same
+    /// rotation to draw the right way up, and the panel needs redrawing afterwards.
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+        self.send_panel_setting();
+    }
+
+    /// Number of refreshes started since the driver was made, however they were started. Lets
+    /// anything keeping track of what is on the panel tell when something else has changed it.
+    pub fn refresh_count(&self) -> u32 {
+        self.refresh_count
+    }
+
+    pub fn is_busy(&mut self) -> bool {
+        !self.busy_pin.level()
+    }
+
+    pub fn busy_wait(&mut self) {
+        if !self.is_busy() {
+            return;
+        }
+        self.trace.record(Event::BusyStart);
+        while self.is_busy() {}
+        self.trace.record(Event::BusyEnd);
+    }
+
+    pub fn reset(&mut self) {
+        self.trace.record(Event::Reset);
+        self.reset_pin.set_level(false);
+        self.delay.delay_ms(10);
+        self.reset_pin.set_level(true);
+        self.delay.delay_ms(10);
+        self.busy_wait();
+    }
+
+    fn select(&mut self) {
+        self.readback.release();
+        self.cs_pin.set_level(false);
+        self.trace.record(Event::Select);
+    }
+
+    fn deselect(&mut self) {
+        self.cs_pin.set_level(true);
+        self.trace.record(Event::Deselect);
+    }
+
+    fn record_data(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.trace.record(Event::Data(byte));
+        }
+    }
+
+    pub fn command<I: IntoIterator<Item = u8>>(&mut self, command: u8, data: I) {
+        if command == constant::DISPLAY_REFRESH {
+            self.refresh_count = self.refresh_count.wrapping_add(1);
+        }
+        self.select();
+
+        // Command mode
+        self.dc_pin.set_level(false);
+        self.spi.write(&[command]);
+        self.trace.record(Event::Command(command));
+
+        let mut data = data.into_iter().peekable();
+        if data.peek().is_some() {
+            // Data mode
+            self.dc_pin.set_level(true);
+
+            let trace = &mut self.trace;
+            self.spi
+                .write_iter(data.inspect(|&byte| trace.record(Event::Data(byte))));
+        }
+        self.deselect();
+    }
+
+    /// Like [`Uc8151::command`], but takes the data as slices and sends them one after the other
+    /// straight from memory, which is several times quicker for large transfers such as frames.
+    pub fn command_chunks<'a, I: IntoIterator<Item = &'a [u8]>>(&mut self, command: u8, chunks: I) {
+        self.select();
+
+        // Command mode
+        self.dc_pin.set_level(false);
+        self.spi.write(&[command]);
+        self.trace.record(Event::Command(command));
+
+        // Data mode
+        self.dc_pin.set_level(true);
+        for chunk in chunks {
+            self.spi.write_fifo(chunk);
+            self.record_data(chunk);
+        }
+
+        self.deselect();
+    }
+
+    /// Sends more data for the last command, e.g. to stream a frame into DTM1 or DTM2 a piece
+    /// at a time.
+    pub fn data<I: IntoIterator<Item = u8>>(&mut self, data: I) {
+        self.select();
+
+        // Data mode
+        self.dc_pin.set_level(true);
+
+        let trace = &mut self.trace;
+        self.spi.write_iter(
+            data.into_iter()
+                .inspect(|&byte| trace.record(Event::Data(byte))),
+        );
+
+        self.deselect();
+    }
+
+    /// Like [`Uc8151::data`], but for data that is already in memory.
+    pub fn data_slice(&mut self, data: &[u8]) {
+        self.select();
+
+        // Data mode
+        self.dc_pin.set_level(true);
+        self.spi.write_fifo(data);
+        self.record_data(data);
+
+        self.deselect();
+    }
+
+    pub fn command_read(&mut self, command: u8, buffer: &mut [u8]) {
+        self.select();
+
+        // Command mode
+        self.dc_pin.set_level(false);
+        self.spi.write(&[command]);
+        self.trace.record(Event::Command(command));
+
+        // Switch to read mode
+        self.readback.acquire();
+        // Switch to data mode
+        self.dc_pin.set_level(true);
+
+        // Bit-bang each bit in, most significant first, eight clocks to a byte
+        for i in 0..buffer.len() * 8 {
+            let byte: usize = i / 8;
+            let bit = i % 8;
+            if bit == 0 {
+                buffer[byte] = 0;
+            }
+            self.readback.set_clock(true);
+            let value = self.readback.data();
+            let value = match value {
+                true => 1 << (7 - bit),
+                false => 0,
+            };
+            buffer[byte] |= value;
+            self.readback.set_clock(false);
+        }
+        for &byte in buffer.iter() {
+            self.trace.record(Event::Read(byte));
+        }
+
+        self.deselect();
+    }
+
+    pub fn profile(&self) -> &PanelProfile {
+        &self.profile
+    }
+
+    pub fn load_luts(&mut self, luts: &Luts) {
+        self.command(constant::VCOM_LUT, luts.vcom);
+        self.command(constant::W2W_LUT, luts.w2w);
+        self.command(constant::B2W_LUT, luts.b2w);
+        self.command(constant::W2B_LUT, luts.w2b);
+        self.command(constant::B2B_LUT, luts.b2b);
+
+        self.pll_control(luts.pll);
+
+        self.busy_wait();
+        self.luts = Some(luts.clone());
+    }
+
+    /// The LUTs last loaded with [`Uc8151::load_luts`] or one of the methods built on it, or
+    /// `None` if none have been loaded since the driver was made or a single LUT has been
+    /// written since.
+    pub fn luts(&self) -> Option<&Luts> {
+        self.luts.as_ref()
+    }
+
+    /// Puts back `luts` as returned by [`Uc8151::luts`] before loading something else, falling
+    /// back on the profile's LUTs if there weren't any.
+    pub fn restore_luts(&mut self, luts: Option<Luts>) {
+        match luts {
+            Some(luts) => self.load_luts(&luts),
+            None => self.default_luts(),
+        }
+    }
+
+    /// Loads the profile's LUTs for the current colour selection, if it has any.
+    pub fn default_luts(&mut self) {
+        if let Some(luts) = self.profile_luts() {
+            self.load_luts(luts);
+        }
+    }
+
+    fn profile_luts(&self) -> Option<&'static Luts> {
+        match self.colour_selection {
+            ColourSelection::BlackWhite => self.profile.luts,
+            ColourSelection::BlackWhiteRed => self.profile.tri_colour_luts,
+        }
+    }
+
+    /// Loads [`luts::TURBO`].
+    pub fn turbo_luts(&mut self) {
+        self.load_luts(&luts::TURBO);
+    }
+
+    /// Loads turbo-like LUTs put together from [`LutSettingGroup`]s.
+    pub fn custom_luts(&mut self) {
+        let vcom = [
+            0x00, 0x01, 0x01, 0x02, 0x00, 0x01, 0x00, 0x02, 0x02, 0x00, 0x00, 0x02, 0x00, 0x02,
+            0x02, 0x03, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00,
+        ];
+
+        let lut_setting_group_white = LutSettingGroup([
+            LutSetting {
+                level_select_1: LevelSelection::Vdh,
+                level_select_2: LevelSelection::Vdh,
+                level_select_3: LevelSelection::Vdh,
+                level_select_4: LevelSelection::Gnd,
+                number_of_frames_1: 1,
+                number_of_frames_2: 1,
+                number_of_frames_3: 2,
+                number_of_frames_4: 0,
+                times_to_repeat: 1,
+            },
+            LutSetting {
+                level_select_1: LevelSelection::Vdh,
+                level_select_2: LevelSelection::Vdl,
+                level_select_3: LevelSelection::Gnd,
+                level_select_4: LevelSelection::Gnd,
+                number_of_frames_1: 2,
+                number_of_frames_2: 2,
+                number_of_frames_3: 0,
+                number_of_frames_4: 0,
+                times_to_repeat: 2,
+            },
+            LutSetting {
+                level_select_1: LevelSelection::Vdl,
+                level_select_2: LevelSelection::Vdl,
+                level_select_3: LevelSelection::Vdl,
+                level_select_4: LevelSelection::Gnd,
+                number_of_frames_1: 2,
+                number_of_frames_2: 2,
+                number_of_frames_3: 3,
+                number_of_frames_4: 0,
+                times_to_repeat: 2,
+            },
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        ]);
+
+        let lut_setting_group_black = LutSettingGroup([
+            LutSetting {
+                level_select_1: LevelSelection::Vdl,
+                level_select_2: LevelSelection::Vdl,
+                level_select_3: LevelSelection::Vdl,
+                level_select_4: LevelSelection::Gnd,
+                number_of_frames_1: 1,
+                number_of_frames_2: 1,
+                number_of_frames_3: 2,
+                number_of_frames_4: 0,
+                times_to_repeat: 1,
+            },
+            LutSetting {
+                level_select_1: LevelSelection::Vdl,
+                level_select_2: LevelSelection::Vdh,
+                level_select_3: LevelSelection::Gnd,
+                level_select_4: LevelSelection::Gnd,
+                number_of_frames_1: 2,
+                number_of_frames_2: 2,
+                number_of_frames_3: 0,
+                number_of_frames_4: 0,
+                times_to_repeat: 2,
+            },
+            LutSetting {
+                level_select_1: LevelSelection::Vdh,
+                level_select_2: LevelSelection::Vdh,
+                level_select_3: LevelSelection::Vdh,
+                level_select_4: LevelSelection::Gnd,
+                number_of_frames_1: 2,
+                number_of_frames_2: 2,
+                number_of_frames_3: 3,
+                number_of_frames_4: 0,
+                times_to_repeat: 2,
+            },
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        ]);
+
+        // self.command(constant::W2W_LUT, [
+        //   0x54, 0x01, 0x01, 0x02, 0x00, 0x01,
+        //   0x60, 0x02, 0x02, 0x00, 0x00, 0x02,
+        //   0xa8, 0x02, 0x02, 0x03, 0x00, 0x02,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+        // ]);
+
+        let w2w = lut_setting_group_white.clone().into();
+
+        // self.command(constant::B2W_LUT, [
+        //   0x54, 0x01, 0x01, 0x02, 0x00, 0x01,
+        //   0x60, 0x02, 0x02, 0x00, 0x00, 0x02,
+        //   0xa8, 0x02, 0x02, 0x03, 0x00, 0x02,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+        // ]);
+
+        let b2w = lut_setting_group_white.into();
+
+        // self.command(constant::W2B_LUT, [
+        //   0xa8, 0x01, 0x01, 0x02, 0x00, 0x01,
+        //   0x60, 0x02, 0x02, 0x00, 0x00, 0x02,
+        //   0x54, 0x02, 0x02, 0x03, 0x00, 0x02,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+        // ]);
+
+        let w2b = lut_setting_group_black.clone().into();
+
+        // self.command(constant::B2B_LUT, [
+        //   0xa8, 0x01, 0x01, 0x02, 0x00, 0x01,
+        //   0x60, 0x02, 0x02, 0x00, 0x00, 0x02,
+        //   0x54, 0x02, 0x02, 0x03, 0x00, 0x02,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        //   0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+        // ]);
+        let b2b = lut_setting_group_black.into();
+
+        self.load_luts(&Luts {
+            vcom,
+            w2w,
+            b2w,
+            w2b,
+            b2b,
+            pll: PllClockFrequency::_200Hz,
+        });
+    }
+
+    pub fn grayscale_luts(&mut self) {
+        self.load_luts(&Luts {
+            vcom: grayscale::vcom_lut().into(),
+            w2w: GrayLevel::White.lut().into(),
+            b2w: GrayLevel::LightGray.lut().into(),
+            w2b: GrayLevel::DarkGray.lut().into(),
+            b2b: GrayLevel::Black.lut().into(),
+            pll: PllClockFrequency::_100Hz,
+        });
+    }
+
+    /// Shows a 4-level image, then puts back the LUTs that were loaded before.
+    ///
+    /// The gray levels come from LUTs loaded into the registers, so the panel must be in
+    /// black/white mode with a profile that has LUTs of its own.
+    pub fn display_grayscale<const BANKS: usize, const LINES: usize>(
+        &mut self,
+        framebuffer: &GrayFramebuffer<BANKS, LINES>,
+    ) -> Result<(), RegionError> {
+        self.check_frame_size::<BANKS, LINES>()?;
+        self.check_register_luts()?;
+
+        let luts = self.luts.take();
+        self.grayscale_luts();
+
+        self.data_start_transmission_1(framebuffer.plane_1());
+        self.data_start_transmission_2(framebuffer.plane_2());
+        self.data_stop();
+        self.display_refresh(true);
+
+        self.restore_luts(luts);
+        Ok(())
+    }
+
+    /// Checks the controller takes its LUTs from the registers in black/white mode, as it has to
+    /// for anything that loads LUTs of its own to make a difference.
+    pub(crate) fn check_register_luts(&self) -> Result<(), RegionError> {
+        if self.colour_selection != ColourSelection::BlackWhite {
+            return Err(RegionError::ColourSelection);
+        }
+        if self.profile_luts().is_none() {
+            return Err(RegionError::OtpLuts);
+        }
+        Ok(())
+    }
+
+    /// Shows a black/white/red image. The panel must have been set up in
+    /// `ColourSelection::BlackWhiteRed` mode.
+    ///
+    /// A tri-colour refresh takes in the region of 15 seconds, so pass `blocking: false` to get
+    /// on with other work and poll [`Uc8151::is_busy`] instead.
+    pub fn display_tri_colour<const BANKS: usize, const LINES: usize>(
+        &mut self,
+        framebuffer: &TriColourFramebuffer<BANKS, LINES>,
+        blocking: bool,
+    ) -> Result<(), RegionError> {
+        self.check_frame_size::<BANKS, LINES>()?;
+        self.check_tri_colour()?;
+
+        self.data_start_transmission_1(framebuffer.black_plane());
+        self.data_start_transmission_2(framebuffer.red_plane());
+        self.data_stop();
+        self.display_refresh(blocking);
+
+        Ok(())
+    }
+
+    pub(crate) fn check_tri_colour(&self) -> Result<(), RegionError> {
+        if self.colour_selection != ColourSelection::BlackWhiteRed {
+            return Err(RegionError::ColourSelection);
+        }
+        Ok(())
+    }
+
+    fn send_panel_setting(&mut self) {
+        let lut_selection = match self.profile_luts() {
+            Some(_) => LutSelection::FromRegister,
+            None => LutSelection::FromOtp,
+        };
+
+        self.panel_setting(PanelSetting {
+            resolution: self.profile.resolution().unwrap_or(Resolution::Res160x296),
+            lut_selection,
+            colour_selection: self.colour_selection,
+            gate_scan_direction: self.rotation.gate_scan_direction(),
+            source_shift_direction: self.rotation.source_shift_direction(),
+            booster_enable: BoosterEnable::On,
+            soft_reset: SoftReset::None,
+        });
+    }
+
+    pub fn setup(&mut self) {
+        self.reset();
+
+        self.send_panel_setting();
+        if self.profile.resolution().is_none() {
+            self.resolution_setting(ResolutionSetting {
+                horizontal: self.profile.width as u8,
+                vertical: self.profile.height,
+            });
+        }
+
+        self.default_luts();
+        // self.turbo_luts();
+
+        self.power_setting(self.profile.power_setting);
+
+        self.power_on(true);
+
+        self.booster_soft_start(self.profile.booster_soft_start);
+
+        self.power_off_sequence_setting(PowerOffSequence::Frame1);
+        // TSE
+        self.command(0x41, [0x00]);
+        // TCON
+        self.command(0x60, [0x22]);
+        // CDI
+        self.command(0x50, [0b01_00_1100]);
+
+        // self.pll_control(PllClockFrequency::_100Hz);
+        // self.power_off();
+    }
+
+    pub fn panel_setting(&mut self, setting: PanelSetting) {
+        let setting_bytes: [u8; 1] = setting.into();
+        self.command(constant::PANEL_SETTING, setting_bytes);
+    }
+
+    pub fn power_setting(&mut self, setting: PowerSetting) {
+        let setting_bytes: [u8; 5] = setting.into();
+        self.command(constant::POWER_SETTING, setting_bytes);
+    }
+
+    pub fn power_off(&mut self) {
+        self.command(constant::POWER_OFF, []);
+    }
+
+    pub fn power_off_sequence_setting(&mut self, setting: PowerOffSequence) {
+        let setting_bytes: [u8; 1] = setting.into();
+        self.command(constant::POWER_OFF_SEQUENCE_SETTINGS, setting_bytes);
+    }
+
+    pub fn power_on(&mut self, blocking: bool) {
+        self.command(constant::POWER_ON, []);
+        if blocking {
+            self.busy_wait();
+        }
+    }
+
+    pub fn power_on_measure(&mut self) {
+        self.command(constant::POWER_ON_MEASURE, []);
+    }
+
+    pub fn booster_soft_start(&mut self, setting: BoosterSoftStart) {
+        let setting_bytes: [u8; 3] = setting.into();
+        self.command(constant::BOOSTER_SOFT_START, setting_bytes);
+    }
+
+    pub fn deep_sleep(&mut self) {
+        self.command(constant::DEEP_SLEEP, [0xA5]);
+    }
+
+    pub fn data_start_transmission_1<I: IntoIterator<Item = u8>>(&mut self, data: I) {
+        self.command(constant::DISPLAY_START_TRANSMISSION_1, data)
+    }
+
+    pub fn data_start_transmission_1_slice(&mut self, data: &[u8]) {
+        self.command_chunks(constant::DISPLAY_START_TRANSMISSION_1, [data])
+    }
+
+    pub fn data_stop(&mut self) -> bool {
+        let mut buf = [0u8; 1];
+        self.command_read(constant::DATA_STOP, &mut buf);
+        buf[0] != 0
+    }
+
+    pub fn display_refresh(&mut self, blocking: bool) {
+        self.command(constant::DISPLAY_REFRESH, []);
+        if blocking {
+            self.busy_wait();
+        }
+    }
+
+    pub fn data_start_transmission_2<I: IntoIterator<Item = u8>>(&mut self, data: I) {
+        self.command(constant::DISPLAY_START_TRANSMISSION_2, data)
+    }
+
+    pub fn data_start_transmission_2_slice(&mut self, data: &[u8]) {
+        self.command_chunks(constant::DISPLAY_START_TRANSMISSION_2, [data])
+    }
+
+    pub fn vcom_lut(&mut self, setting: LutSettingGroup) {
+        let setting_bytes: [u8; 44] = setting.into();
+        self.command(constant::VCOM_LUT, setting_bytes);
+        self.luts = None;
+    }
+
+    pub fn w2w_lut(&mut self, setting: LutSettingGroup) {
+        let setting_bytes: [u8; 42] = setting.into();
+        self.command(constant::W2W_LUT, setting_bytes);
+        self.luts = None;
+    }
+
+    pub fn b2w_lut(&mut self, setting: LutSettingGroup) {
+        let setting_bytes: [u8; 42] = setting.into();
+        self.command(constant::B2W_LUT, setting_bytes);
+        self.luts = None;
+    }
+
+    pub fn w2b_lut(&mut self, setting: LutSettingGroup) {
+        let setting_bytes: [u8; 42] = setting.into();
+        self.command(constant::W2B_LUT, setting_bytes);
+        self.luts = None;
+    }
+
+    pub fn b2b_lut(&mut self, setting: LutSettingGroup) {
+        let setting_bytes: [u8; 42] = setting.into();
+        self.command(constant::B2B_LUT, setting_bytes);
+        self.luts = None;
+    }
+
+    pub fn pll_control(&mut self, setting: PllClockFrequency) {
+        let setting_bytes: [u8; 1] = setting.into();
+        self.command(constant::PLL_CONTROL, setting_bytes);
+        if let Some(luts) = &mut self.luts {
+            luts.pll = setting;
+        }
+    }
+
+    pub fn temperature_sensor_calibration(&mut self) {}
+
+    pub fn temperature_sensor_enable(&mut self) {}
+
+    pub fn temperature_sensor_write(&mut self) {}
+
+    pub fn temperature_sensor_read(&mut self) {}
+
+    pub fn vcom_and_data_interval_setting(&mut self) {}
+
+    pub fn low_power_detection(&mut self) {}
+
+    pub fn tcon_setting(&mut self) {}
+
+    pub fn resolution_setting(&mut self, setting: ResolutionSetting) {
+        let setting_bytes: [u8; 3] = setting.into();
+        self.command(constant::RESOLUTION_SETTING, setting_bytes);
+    }
+
+    pub fn revision(&mut self) {}
+
+    pub fn get_status(&mut self) {}
+
+    pub fn auto_measure_vcom(&mut self) {}
+
+    pub fn vcom_value(&mut self) {}
+
+    pub fn vcom_dc_setting(&mut self) {}
+
+    /// Sets the partial window, after checking it fits the panel.
+    pub fn partial_window(&mut self, setting: PartialWindow) -> Result<(), PartialWindowError> {
+        let setting = PartialWindow::new(
+            setting.horizontal_start_channel_bank,
+            setting.horizontal_end_channel_bank,
+            setting.vertical_start_line,
+            setting.vertical_end_line,
+            setting.partial_scan,
+            self.profile.width,
+            self.profile.height,
+        )?;
+        let setting_bytes: [u8; 7] = setting.into();
+        self.command(constant::PARTIAL_WINDOW, setting_bytes);
+        Ok(())
+    }
+
+    pub fn partial_in(&mut self) {
+        self.command(constant::PARTIAL_IN, []);
+    }
+
+    pub fn partial_out(&mut self) {
+        self.command(constant::PARTIAL_OUT, []);
+    }
+
+    fn frame_len(&self) -> usize {
+        self.profile.frame_len()
+    }
+
+    pub(crate) fn check_framebuffer<const BANKS: usize, const LINES: usize>(
+        &self,
+        _framebuffer: &Framebuffer<BANKS, LINES>,
+    ) -> Result<(), RegionError> {
+        self.check_frame_size::<BANKS, LINES>()
+    }
+
+    /// Checks a frame of `BANKS` by `LINES`, of any kind, is the size of the panel.
+    pub(crate) fn check_frame_size<const BANKS: usize, const LINES: usize>(
+        &self,
+    ) -> Result<(), RegionError> {
+        let banks = (self.profile.width as usize).div_ceil(8);
+        if BANKS != banks || LINES != self.profile.height as usize {
+            return Err(RegionError::FramebufferSize);
+        }
+        Ok(())
+    }
+
+    /// Redraws the whole panel from `framebuffer` using the full waveform.
+    pub fn full_refresh<const BANKS: usize, const LINES: usize>(
+        &mut self,
+        framebuffer: &Framebuffer<BANKS, LINES>,
+    ) -> Result<(), RegionError> {
+        self.check_framebuffer(framebuffer)?;
+
+        self.data_start_transmission_2_slice(framebuffer.as_bytes());
+        self.data_stop();
+        self.display_refresh(true);
+
+        Ok(())
+    }
+
+    /// Drives the whole panel black then white `cycles` times to clear out residual images.
+    ///
+    /// This uses the profile's full refresh LUTs, whatever was loaded before, and puts the
+    /// previous ones back afterwards. On a tri-colour panel the red plane is left clear.
+    pub fn deep_clean(&mut self, cycles: u8) {
+        let luts = self.luts.take();
+        self.default_luts();
+
+        let frame_len = self.frame_len();
+        for _ in 0..cycles {
+            for fill in [0xff, 0x00] {
+                // DTM1 is the old frame in black/white mode, but the black plane in
+                // black/white/red mode, where DTM2 is the red plane.
+                let (dtm1, dtm2) = match self.colour_selection {
+                    ColourSelection::BlackWhite => (!fill, fill),
+                    ColourSelection::BlackWhiteRed => (fill, 0x00),
+                };
+                self.data_start_transmission_1((0..frame_len).map(|_| dtm1));
+                self.data_start_transmission_2((0..frame_len).map(|_| dtm2));
+                self.data_stop();
+                self.display_refresh(true);
+            }
+        }
+
+        if let Some(luts) = luts {
+            self.load_luts(&luts);
+        }
+    }
+
+    /// Redraws `region` of the panel from `framebuffer` using a partial refresh.
+    ///
+    /// `region` is in the coordinates of the current rotation, and `framebuffer` must be the
+    /// size of the panel. The region is widened to whole channel banks before the matching part
+    /// of the frame is sent.
+    pub fn update_region<const BANKS: usize, const LINES: usize>(
+        &mut self,
+        region: Region,
+        framebuffer: &Framebuffer<BANKS, LINES>,
+    ) -> Result<(), RegionError> {
+        self.check_region(region)?;
+        let sources = self.profile.width;
+        self.update_panel_region(self.rotation.region_to_panel(region, sources), framebuffer)
+    }
+
+    /// Width and height of the panel as seen in the current rotation.
+    pub fn size(&self) -> (u16, u16) {
+        let (width, height) = self
+            .rotation
+            .size(self.profile.width as usize, self.profile.height as usize);
+        (width as u16, height as u16)
+    }
+
+    /// Checks `region`, in the coordinates of the current rotation, is non-empty and on the
+    /// panel.
+    pub fn check_region(&self, region: Region) -> Result<(), RegionError> {
+        let (width, height) = self.size();
+        region.check(width, height)
+    }
+
+    /// Like [`Uc8151::update_region`], but with `region` in the panel's own coordinates,
+    /// regardless of the rotation.
+    pub fn update_panel_region<const BANKS: usize, const LINES: usize>(
+        &mut self,
+        region: Region,
+        framebuffer: &Framebuffer<BANKS, LINES>,
+    ) -> Result<(), RegionError> {
+        self.check_framebuffer(framebuffer)?;
+        region.check(self.profile.width, self.profile.height)?;
+        let region = region.align_to_banks();
+        let window = region.partial_window(self.profile.width, self.profile.height)?;
+
+        let first_bank = region.first_bank() as usize;
+        let last_bank = region.last_bank() as usize;
+        let lines = region.first_line() as usize..=region.last_line() as usize;
+
+        self.partial_in();
+        self.partial_window(window)?;
+        self.command_chunks(
+            constant::DISPLAY_START_TRANSMISSION_2,
+            lines.map(|line| framebuffer.line_bytes(line, first_bank, last_bank)),
+        );
+        self.data_stop();
+        self.display_refresh(true);
+        self.partial_out();
+
+        Ok(())
+    }
+
+    pub fn program_mode(&mut self) {}
+
+    pub fn active_program(&mut self) {}
+
+    pub fn read_otp_data(&mut self) {}
+
+    pub fn cascade_setting(&mut self) {}
+
+    pub fn power_saving(&mut self) {}
+
+    pub fn force_temperature(&mut self) {}
+}
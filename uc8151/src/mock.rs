@@ -0,0 +1,221 @@
+//! Expectation mocks of the hardware, for checking exactly what the driver does on the wire.
+//!
+//! A [`Mock`] is made with the [`Transaction`]s the driver is expected to make, in order. Pin
+//! changes, SPI writes, delays and the readback clock are recorded as the driver makes them,
+//! and BUSY and readback data levels are answered from the expectations. [`Mock::done`] then
+//! checks the driver did exactly what was expected and no more.
+
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::rc::Rc;
+use std::vec::Vec;
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi::{Write, WriteIter};
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+use embedded_hal::spi::FullDuplex;
+
+use super::interface;
+use super::profile::PanelProfile;
+use super::Uc8151;
+
+/// The driver as wired up to a [`Mock`].
+pub type Display = Uc8151<Spi, Pin, Pin, Busy, Pin, Delay, Readback>;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Transaction {
+    Cs(bool),
+    Dc(bool),
+    Reset(bool),
+    /// Bytes written over SPI. Writes with no pin change in between come out together.
+    Write(Vec<u8>),
+    Delay(u32),
+    /// A read of the BUSY line, and the level to answer with. High means idle.
+    Busy(bool),
+    /// The readback lines handed back to SPI.
+    Release,
+    /// The readback lines taken over as GPIO.
+    Acquire,
+    Clock(bool),
+    /// A read of the data line while it is taken over, and the level to answer with.
+    Data(bool),
+}
+
+struct State {
+    expected: Vec<Transaction>,
+    actual: Vec<Transaction>,
+    /// Bytes waiting in the SPI receive FIFO.
+    received: usize,
+}
+
+impl State {
+    fn record(&mut self, transaction: Transaction) {
+        match (self.actual.last_mut(), transaction) {
+            (Some(Transaction::Write(bytes)), Transaction::Write(more)) => bytes.extend(more),
+            (_, transaction) => self.actual.push(transaction),
+        }
+    }
+
+    /// Answers a read from the next expectation, which must be a read of the same line.
+    fn answer(&mut self, read: fn(bool) -> Transaction) -> bool {
+        let index = self.actual.len();
+        let level = match (read(false), self.expected.get(index)) {
+            (Transaction::Busy(_), Some(Transaction::Busy(level)))
+            | (Transaction::Data(_), Some(Transaction::Data(level))) => *level,
+            (_, next) => panic!(
+                "transaction {} was {:?}, but expected {:?} after {:?}",
+                index,
+                read(false),
+                next,
+                self.actual
+            ),
+        };
+        self.actual.push(read(level));
+        level
+    }
+}
+
+/// Shared state behind all the mocks for one driver.
+#[derive(Clone)]
+pub struct Mock(Rc<RefCell<State>>);
+
+impl Mock {
+    pub fn new(expected: Vec<Transaction>) -> Self {
+        Self(Rc::new(RefCell::new(State {
+            expected,
+            actual: Vec::new(),
+            received: 0,
+        })))
+    }
+
+    /// Leaves `bytes` in the SPI receive FIFO, as if from an earlier transfer.
+    pub fn receive(&self, bytes: usize) {
+        self.0.borrow_mut().received += bytes;
+    }
+
+    /// The driver for `profile`, wired up to this mock.
+    pub fn display(&self, profile: PanelProfile) -> Display {
+        Uc8151::new(
+            Spi(self.clone()),
+            Pin(self.clone(), Transaction::Dc),
+            Pin(self.clone(), Transaction::Cs),
+            Busy(self.clone()),
+            Pin(self.clone(), Transaction::Reset),
+            Delay(self.clone()),
+            Readback(self.clone()),
+            profile,
+        )
+    }
+
+    /// Checks that everything expected has happened, and nothing else.
+    pub fn done(&self) {
+        let state = self.0.borrow();
+        assert_eq!(state.expected, state.actual);
+    }
+
+    fn record(&self, transaction: Transaction) {
+        self.0.borrow_mut().record(transaction);
+    }
+}
+
+/// The SPI peripheral. Every byte written is also read back as a zero.
+pub struct Spi(Mock);
+
+impl Write<u8> for Spi {
+    type Error = Infallible;
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Infallible> {
+        self.0.record(Transaction::Write(bytes.to_vec()));
+        Ok(())
+    }
+}
+
+impl WriteIter<u8> for Spi {
+    type Error = Infallible;
+
+    fn write_iter<WI: IntoIterator<Item = u8>>(&mut self, bytes: WI) -> Result<(), Infallible> {
+        self.0
+            .record(Transaction::Write(bytes.into_iter().collect()));
+        Ok(())
+    }
+}
+
+impl FullDuplex<u8> for Spi {
+    type Error = Infallible;
+
+    fn read(&mut self) -> nb::Result<u8, Infallible> {
+        let mut state = (self.0).0.borrow_mut();
+        match state.received {
+            0 => Err(nb::Error::WouldBlock),
+            _ => {
+                state.received -= 1;
+                Ok(0)
+            }
+        }
+    }
+
+    fn send(&mut self, byte: u8) -> nb::Result<(), Infallible> {
+        self.0.record(Transaction::Write(vec![byte]));
+        self.0.receive(1);
+        Ok(())
+    }
+}
+
+/// DC, CS or RESET, recorded as the transaction for that line.
+pub struct Pin(Mock, fn(bool) -> Transaction);
+
+impl OutputPin for Pin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.record((self.1)(false));
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.record((self.1)(true));
+        Ok(())
+    }
+}
+
+pub struct Busy(Mock);
+
+impl InputPin for Busy {
+    type Error = Infallible;
+
+    fn is_high(&self) -> Result<bool, Infallible> {
+        Ok((self.0).0.borrow_mut().answer(Transaction::Busy))
+    }
+
+    fn is_low(&self) -> Result<bool, Infallible> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+pub struct Delay(Mock);
+
+impl DelayMs<u32> for Delay {
+    fn delay_ms(&mut self, ms: u32) {
+        self.0.record(Transaction::Delay(ms));
+    }
+}
+
+pub struct Readback(Mock);
+
+impl interface::Readback for Readback {
+    fn release(&mut self) {
+        self.0.record(Transaction::Release);
+    }
+
+    fn acquire(&mut self) {
+        self.0.record(Transaction::Acquire);
+    }
+
+    fn set_clock(&mut self, high: bool) {
+        self.0.record(Transaction::Clock(high));
+    }
+
+    fn data(&mut self) -> bool {
+        (self.0).0.borrow_mut().answer(Transaction::Data)
+    }
+}
@@ -0,0 +1,143 @@
+//! Descriptions of the panels that can be driven by a UC8151.
+//!
+//! The controller is used on glass of several sizes, with and without red pigment, and each
+//! panel wants its own power and booster settings. A [`PanelProfile`] collects them so the
+//! driver can be set up for whichever one is attached.
+
+use super::luts::{self, Luts};
+use super::register::*;
+
+#[derive(Clone)]
+pub struct PanelProfile {
+    /// Number of source outputs in use, i.e. pixels along a gate line.
+    pub width: u16,
+    /// Number of gate lines in use.
+    pub height: u16,
+    /// Colours the panel can show.
+    pub colour: ColourSelection,
+    pub power_setting: PowerSetting,
+    pub booster_soft_start: BoosterSoftStart,
+    /// LUTs for black/white refreshes, or `None` to use the ones in the panel's OTP.
+    pub luts: Option<&'static Luts>,
+    /// LUTs for black/white/red refreshes, or `None` to use the ones in the panel's OTP.
+    ///
+    /// In black/white/red mode the controller takes `b2w` as the LUT for red pixels, `w2b` for
+    /// white and `b2b` for black, and doesn't use `w2w`. Red waveforms depend on the batch of
+    /// glass, so the profiles here leave them to OTP.
+    pub tri_colour_luts: Option<&'static Luts>,
+}
+
+impl PanelProfile {
+    /// Bytes in one bit-plane of a full frame.
+    pub const fn frame_len(&self) -> usize {
+        (self.width as usize).div_ceil(8) * self.height as usize
+    }
+
+    /// The panel setting resolution matching this panel, if there is one. Other sizes are set
+    /// with the resolution setting register.
+    pub fn resolution(&self) -> Option<Resolution> {
+        [
+            Resolution::Res96x230,
+            Resolution::Res96x262,
+            Resolution::Res129x296,
+            Resolution::Res160x296,
+        ]
+        .iter()
+        .copied()
+        .find(|resolution| resolution.sources() == self.width && resolution.gates() == self.height)
+    }
+}
+
+const BOOSTER_SOFT_START: BoosterSoftStart = BoosterSoftStart {
+    phase_a: BoosterPhaseSetting {
+        soft_start_period: SoftStartPeriod::_10Ms,
+        driving_strength: DrivingStrength::Strength3,
+        minimum_off_time: MinimumOffTime::_6_58Us,
+    },
+    phase_b: BoosterPhaseSetting {
+        soft_start_period: SoftStartPeriod::_10Ms,
+        driving_strength: DrivingStrength::Strength3,
+        minimum_off_time: MinimumOffTime::_6_58Us,
+    },
+    phase_c: BoosterPhaseSetting {
+        soft_start_period: SoftStartPeriod::_10Ms,
+        driving_strength: DrivingStrength::Strength3,
+        minimum_off_time: MinimumOffTime::_6_58Us,
+    },
+};
+
+const BLACK_WHITE_POWER_SETTING: PowerSetting = PowerSetting {
+    source_power: PowerSelection::Internal,
+    gate_power: PowerSelection::Internal,
+    vcom_voltage: VcomVoltage::Vdh,
+    vgh_vgl_voltage: VghVglVoltage::V16,
+    internal_vdh_voltage: InternalVoltage::V11_0,
+    internal_vdl_voltage: InternalVoltage::V11_0,
+    internal_vdhr_voltage: InternalVoltage::V11_0,
+};
+
+// VDHR drives the red particles, so tri-colour panels keep the datasheet defaults.
+const BLACK_WHITE_RED_POWER_SETTING: PowerSetting = PowerSetting {
+    source_power: PowerSelection::Internal,
+    gate_power: PowerSelection::Internal,
+    vcom_voltage: VcomVoltage::Vdh,
+    vgh_vgl_voltage: VghVglVoltage::V16,
+    internal_vdh_voltage: InternalVoltage::V10_0,
+    internal_vdl_voltage: InternalVoltage::V10_0,
+    internal_vdhr_voltage: InternalVoltage::V3_0,
+};
+
+/// 2.9" 128x296 black/white, as fitted to the Badger 2040.
+pub const PANEL_2IN9_128X296: PanelProfile = PanelProfile {
+    width: 128,
+    height: 296,
+    colour: ColourSelection::BlackWhite,
+    power_setting: BLACK_WHITE_POWER_SETTING,
+    booster_soft_start: BOOSTER_SOFT_START,
+    luts: Some(&luts::DEFAULT),
+    tri_colour_luts: None,
+};
+
+/// 2.9" 128x296 black/white/red.
+pub const PANEL_2IN9_128X296_RED: PanelProfile = PanelProfile {
+    width: 128,
+    height: 296,
+    colour: ColourSelection::BlackWhiteRed,
+    power_setting: BLACK_WHITE_RED_POWER_SETTING,
+    booster_soft_start: BOOSTER_SOFT_START,
+    luts: None,
+    tri_colour_luts: None,
+};
+
+/// 2.13" 104x212 black/white.
+pub const PANEL_2IN13_104X212: PanelProfile = PanelProfile {
+    width: 104,
+    height: 212,
+    colour: ColourSelection::BlackWhite,
+    power_setting: BLACK_WHITE_POWER_SETTING,
+    booster_soft_start: BOOSTER_SOFT_START,
+    luts: Some(&luts::DEFAULT),
+    tri_colour_luts: None,
+};
+
+/// 2.13" 104x212 black/white/red.
+pub const PANEL_2IN13_104X212_RED: PanelProfile = PanelProfile {
+    width: 104,
+    height: 212,
+    colour: ColourSelection::BlackWhiteRed,
+    power_setting: BLACK_WHITE_RED_POWER_SETTING,
+    booster_soft_start: BOOSTER_SOFT_START,
+    luts: None,
+    tri_colour_luts: None,
+};
+
+/// 1.54" 152x152 black/white.
+pub const PANEL_1IN54_152X152: PanelProfile = PanelProfile {
+    width: 152,
+    height: 152,
+    colour: ColourSelection::BlackWhite,
+    power_setting: BLACK_WHITE_POWER_SETTING,
+    booster_soft_start: BOOSTER_SOFT_START,
+    luts: Some(&luts::DEFAULT),
+    tri_colour_luts: None,
+};
@@ -0,0 +1,179 @@
+//! Scheduling of full refreshes to keep ghosting under control.
+//!
+//! Partial and fast refreshes skip most of the waveform that shakes the particles loose, so
+//! every one leaves a little of the previous image behind. [`RefreshPolicy`] counts them per
+//! region and turns the next update into a full refresh once a region has had too many, or once
+//! too long has passed since the last full refresh.
+
+use embedded_hal::blocking::delay::DelayMs;
+
+use super::framebuffer::Framebuffer;
+use super::interface::{Bus, Input, Output, Readback};
+use super::luts::Luts;
+use super::region::{Region, RegionError};
+use super::trace::Trace;
+use super::Uc8151;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RefreshKind {
+    /// The whole panel was redrawn with the full waveform.
+    Full,
+    /// Only a region was redrawn, using a partial window.
+    Partial,
+    /// The panel was redrawn with a shortened waveform, such as the turbo LUTs.
+    Fast,
+}
+
+/// Tracks up to `REGIONS` separately counted regions.
+pub struct RefreshPolicy<const REGIONS: usize> {
+    /// Partial or fast refreshes a region may have before the next update is a full refresh.
+    pub max_updates: u16,
+    /// Milliseconds after a full refresh at which the next update is a full refresh.
+    pub max_interval_ms: Option<u32>,
+    /// LUTs to redraw the whole panel with, such as [`luts::TURBO`](super::luts::TURBO), for
+    /// updates that don't need a full refresh. Updates are partial refreshes if this is `None`.
+    pub fast_luts: Option<&'static Luts>,
+    regions: [Option<(Region, u16)>; REGIONS],
+    overflowed: bool,
+    last_full_refresh_ms: u32,
+}
+
+impl<const REGIONS: usize> RefreshPolicy<REGIONS> {
+    pub const fn new(max_updates: u16, max_interval_ms: Option<u32>) -> Self {
+        Self {
+            max_updates,
+            max_interval_ms,
+            fast_luts: None,
+            regions: [None; REGIONS],
+            overflowed: false,
+            last_full_refresh_ms: 0,
+        }
+    }
+
+    /// Number of partial or fast refreshes that have touched `region` since the last full
+    /// refresh.
+    pub fn updates(&self, region: Region) -> u16 {
+        let region = region.align_to_banks();
+        self.regions
+            .iter()
+            .flatten()
+            .filter(|(tracked, _)| tracked.intersects(&region))
+            .map(|(_, count)| *count)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Whether the next update of `region` should be a full refresh.
+    pub fn needs_full_refresh(&self, region: Region, now_ms: u32) -> bool {
+        let expired = match self.max_interval_ms {
+            Some(interval) => now_ms.wrapping_sub(self.last_full_refresh_ms) >= interval,
+            None => false,
+        };
+        self.overflowed || expired || self.updates(region) >= self.max_updates
+    }
+
+    /// Records a refresh that was made without going through [`RefreshPolicy::update`].
+    pub fn record(&mut self, kind: RefreshKind, region: Region, now_ms: u32) {
+        if kind == RefreshKind::Full {
+            self.regions = [None; REGIONS];
+            self.overflowed = false;
+            self.last_full_refresh_ms = now_ms;
+            return;
+        }
+
+        let region = region.align_to_banks();
+        let mut found = false;
+        for (tracked, count) in self.regions.iter_mut().flatten() {
+            if tracked.intersects(&region) {
+                *count = count.saturating_add(1);
+            }
+            found |= *tracked == region;
+        }
+        if found {
+            return;
+        }
+        let count = self.updates(region);
+        match self.regions.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => *slot = Some((region, count.max(1))),
+            // Nowhere left to count it, so play it safe.
+            None => self.overflowed = true,
+        }
+    }
+
+    /// Redraws `region` from `framebuffer`, using a full refresh instead of a partial or fast
+    /// one when the policy calls for it. `region` is in the coordinates of the current rotation.
+    ///
+    /// A fast refresh redraws the whole panel, so it counts against all of it.
+    pub fn update<
+        SPI,
+        DC,
+        CS,
+        BUSY,
+        RESET,
+        DELAY,
+        READBACK,
+        TRACE,
+        const BANKS: usize,
+        const LINES: usize,
+    >(
+        &mut self,
+        display: &mut Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>,
+        region: Region,
+        framebuffer: &Framebuffer<BANKS, LINES>,
+        now_ms: u32,
+    ) -> Result<RefreshKind, RegionError>
+    where
+        SPI: Bus,
+        DC: Output,
+        CS: Output,
+        BUSY: Input,
+        RESET: Output,
+        DELAY: DelayMs<u32>,
+        READBACK: Readback,
+        TRACE: Trace,
+    {
+        display.check_region(region)?;
+        display.check_framebuffer(framebuffer)?;
+
+        let (kind, region) = match self.fast_luts {
+            _ if self.needs_full_refresh(region, now_ms) => {
+                display.full_refresh(framebuffer)?;
+                (RefreshKind::Full, region)
+            }
+            Some(fast_luts) => {
+                let luts = display.luts().cloned();
+                display.load_luts(fast_luts);
+                display.full_refresh(framebuffer)?;
+                display.restore_luts(luts);
+                let (width, height) = display.size();
+                (RefreshKind::Fast, Region::new(0, 0, width, height))
+            }
+            None => {
+                display.update_region(region, framebuffer)?;
+                (RefreshKind::Partial, region)
+            }
+        };
+        self.record(kind, region, now_ms);
+        Ok(kind)
+    }
+
+    /// Runs [`Uc8151::deep_clean`] and starts counting again from a clean panel.
+    pub fn deep_clean<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>(
+        &mut self,
+        display: &mut Uc8151<SPI, DC, CS, BUSY, RESET, DELAY, READBACK, TRACE>,
+        cycles: u8,
+        now_ms: u32,
+    ) where
+        SPI: Bus,
+        DC: Output,
+        CS: Output,
+        BUSY: Input,
+        RESET: Output,
+        DELAY: DelayMs<u32>,
+        READBACK: Readback,
+        TRACE: Trace,
+    {
+        display.deep_clean(cycles);
+        self.record(RefreshKind::Full, Region::new(0, 0, 0, 0), now_ms);
+    }
+}
@@ -0,0 +1,205 @@
+use super::register::*;
+
+/// A rectangle of pixels on the panel.
+///
+/// `x` and `width` run along the source outputs, `y` and `height` along the gate lines.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Region {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RegionError {
+    /// The region has no width or no height.
+    Empty,
+    /// Part of the region lies outside the panel.
+    OutOfBounds,
+    /// The framebuffer is not the size of the panel.
+    FramebufferSize,
+    /// The panel isn't set up for the colours being drawn.
+    ColourSelection,
+    /// The panel uses the LUTs in its OTP, so LUTs loaded into the registers would be ignored.
+    OtpLuts,
+    /// The region can't be made into a partial window for the panel.
+    PartialWindow(PartialWindowError),
+}
+
+impl From<PartialWindowError> for RegionError {
+    fn from(error: PartialWindowError) -> Self {
+        Self::PartialWindow(error)
+    }
+}
+
+impl Region {
+    pub const fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Checks the region is non-empty and fits a `sources` by `gates` panel.
+    pub fn check(&self, sources: u16, gates: u16) -> Result<(), RegionError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(RegionError::Empty);
+        }
+        if self.x as u32 + self.width as u32 > sources as u32
+            || self.y as u32 + self.height as u32 > gates as u32
+        {
+            return Err(RegionError::OutOfBounds);
+        }
+        Ok(())
+    }
+
+    pub fn intersects(&self, other: &Region) -> bool {
+        let (x, y) = (self.x as u32, self.y as u32);
+        let (other_x, other_y) = (other.x as u32, other.y as u32);
+        x < other_x + other.width as u32
+            && other_x < x + self.width as u32
+            && y < other_y + other.height as u32
+            && other_y < y + self.height as u32
+    }
+
+    /// Whether `other` lies entirely inside this region.
+    pub fn contains(&self, other: &Region) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.x as u32 + other.width as u32 <= self.x as u32 + self.width as u32
+            && other.y as u32 + other.height as u32 <= self.y as u32 + self.height as u32
+    }
+
+    /// The smallest region covering both this region and `other`, as far as the coordinates
+    /// go.
+    pub fn union(&self, other: &Region) -> Region {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let end = |start: u16, len: u16| (start as u32 + len as u32).min(u16::MAX as u32);
+        Region {
+            x,
+            y,
+            width: (end(self.x, self.width).max(end(other.x, other.width)) - x as u32) as u16,
+            height: (end(self.y, self.height).max(end(other.y, other.height)) - y as u32) as u16,
+        }
+    }
+
+    pub fn area(&self) -> u32 {
+        self.width as u32 * self.height as u32
+    }
+
+    /// Grows the region horizontally so it starts and ends on 8-pixel channel bank boundaries,
+    /// as far as the coordinates go.
+    pub fn align_to_banks(&self) -> Self {
+        let start = self.x / 8 * 8;
+        let end = ((self.x as u32 + self.width as u32).div_ceil(8) * 8).min(u16::MAX as u32);
+        Self {
+            x: start,
+            width: (end - start as u32) as u16,
+            ..*self
+        }
+    }
+
+    pub fn first_bank(&self) -> u8 {
+        (self.x / 8) as u8
+    }
+
+    /// The bank holding the last pixel. An empty region has none, so this is then the bank
+    /// before the first, or the first at the edge of the panel.
+    pub fn last_bank(&self) -> u8 {
+        (last(self.x, self.width) / 8) as u8
+    }
+
+    pub fn first_line(&self) -> u16 {
+        self.y
+    }
+
+    /// The last line, or as for [`Region::last_bank`] the one before the first if there are none.
+    pub fn last_line(&self) -> u16 {
+        last(self.y, self.height)
+    }
+
+    /// The partial window covering this region on a `sources` by `gates` panel, for a region
+    /// already aligned to banks.
+    pub fn partial_window(
+        &self,
+        sources: u16,
+        gates: u16,
+    ) -> Result<PartialWindow, PartialWindowError> {
+        PartialWindow::new(
+            self.first_bank(),
+            self.last_bank(),
+            self.first_line(),
+            self.last_line(),
+            false,
+            sources,
+            gates,
+        )
+    }
+}
+
+/// The last coordinate of a run of `len` from `start`, as far as the coordinates go.
+fn last(start: u16, len: u16) -> u16 {
+    (start as u32 + len as u32)
+        .saturating_sub(1)
+        .min(u16::MAX as u32) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_align_to_banks() {
+        let region = Region::new(13, 4, 6, 2).align_to_banks();
+        assert_eq!(Region::new(8, 4, 16, 2), region);
+        assert_eq!((1, 2), (region.first_bank(), region.last_bank()));
+        assert_eq!((4, 5), (region.first_line(), region.last_line()));
+
+        let region = Region::new(65530, 0, 100, 1);
+        assert_eq!(Region::new(65528, 0, 7, 1), region.align_to_banks());
+        assert!(region.intersects(&Region::new(65535, 0, 1, 1)));
+    }
+
+    #[test]
+    fn test_empty_ends() {
+        let region = Region::new(0, 0, 0, 0);
+        assert_eq!((0, 0), (region.last_bank(), region.last_line()));
+        let region = Region::new(16, 4, 0, 0);
+        assert_eq!((1, 3), (region.last_bank(), region.last_line()));
+        let region = Region::new(0, 65535, 8, 2);
+        assert_eq!(65535, region.last_line());
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(Ok(()), Region::new(120, 290, 8, 6).check(128, 296));
+        assert_eq!(
+            Err(RegionError::OutOfBounds),
+            Region::new(121, 0, 8, 1).check(128, 296)
+        );
+        assert_eq!(
+            Err(RegionError::Empty),
+            Region::new(0, 0, 0, 1).check(128, 296)
+        );
+    }
+
+    #[test]
+    fn test_union_and_contains() {
+        let a = Region::new(0, 0, 8, 8);
+        let b = Region::new(16, 4, 8, 8);
+        let union = a.union(&b);
+        assert_eq!(Region::new(0, 0, 24, 12), union);
+        assert!(union.contains(&a) && union.contains(&b));
+        assert!(!a.intersects(&b));
+
+        let edge = Region::new(65530, 65530, 100, 100);
+        let union = Region::new(0, 0, 8, 8).union(&edge);
+        assert_eq!(Region::new(0, 0, 65535, 65535), union);
+        assert!(edge.contains(&Region::new(65535, 65535, 1, 1)));
+        assert!(!union.contains(&edge));
+    }
+}
@@ -0,0 +1,116 @@
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub struct BoosterSoftStart {
+    pub phase_a: BoosterPhaseSetting,
+    pub phase_b: BoosterPhaseSetting,
+    pub phase_c: BoosterPhaseSetting,
+}
+
+impl From<BoosterSoftStart> for [u8; 3] {
+    fn from(settings: BoosterSoftStart) -> [u8; 3] {
+        [
+            settings.phase_a.into(),
+            settings.phase_b.into(),
+            settings.phase_c.into(),
+        ]
+    }
+}
+
+impl From<[u8; 3]> for BoosterSoftStart {
+    fn from(bytes: [u8; 3]) -> BoosterSoftStart {
+        BoosterSoftStart {
+            phase_a: bytes[0].into(),
+            phase_b: bytes[1].into(),
+            phase_c: bytes[2].into(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct BoosterPhaseSetting {
+    pub soft_start_period: SoftStartPeriod,
+    pub driving_strength: DrivingStrength,
+    pub minimum_off_time: MinimumOffTime,
+}
+
+impl From<BoosterPhaseSetting> for u8 {
+    fn from(settings: BoosterPhaseSetting) -> u8 {
+        settings.soft_start_period as u8
+            | settings.driving_strength as u8
+            | settings.minimum_off_time as u8
+    }
+}
+
+impl From<u8> for BoosterPhaseSetting {
+    fn from(byte: u8) -> BoosterPhaseSetting {
+        BoosterPhaseSetting {
+            soft_start_period: match byte & 0o300 {
+                0o000 => SoftStartPeriod::_10Ms,
+                0o100 => SoftStartPeriod::_20Ms,
+                0o200 => SoftStartPeriod::_30Ms,
+                _ => SoftStartPeriod::_40Ms,
+            },
+            driving_strength: match byte & 0o070 {
+                0o000 => DrivingStrength::Strength1,
+                0o010 => DrivingStrength::Strength2,
+                0o020 => DrivingStrength::Strength3,
+                0o030 => DrivingStrength::Strength4,
+                0o040 => DrivingStrength::Strength5,
+                0o050 => DrivingStrength::Strength6,
+                0o060 => DrivingStrength::Strength7,
+                _ => DrivingStrength::Strength8,
+            },
+            minimum_off_time: match byte & 0o007 {
+                0o000 => MinimumOffTime::_0_27Us,
+                0o001 => MinimumOffTime::_0_34Us,
+                0o002 => MinimumOffTime::_0_40Us,
+                0o003 => MinimumOffTime::_0_54Us,
+                0o004 => MinimumOffTime::_0_80Us,
+                0o005 => MinimumOffTime::_1_54Us,
+                0o006 => MinimumOffTime::_3_34Us,
+                _ => MinimumOffTime::_6_58Us,
+            },
+        }
+    }
+}
+
+impl Default for BoosterPhaseSetting {
+    fn default() -> Self {
+        Self {
+            soft_start_period: SoftStartPeriod::_10Ms,
+            driving_strength: DrivingStrength::Strength3,
+            minimum_off_time: MinimumOffTime::_6_58Us,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SoftStartPeriod {
+    _10Ms = 0o000,
+    _20Ms = 0o100,
+    _30Ms = 0o200,
+    _40Ms = 0o300,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DrivingStrength {
+    Strength1 = 0o000,
+    Strength2 = 0o010,
+    Strength3 = 0o020,
+    Strength4 = 0o030,
+    Strength5 = 0o040,
+    Strength6 = 0o050,
+    Strength7 = 0o060,
+    Strength8 = 0o070,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MinimumOffTime {
+    _0_27Us = 0o000,
+    _0_34Us = 0o001,
+    _0_40Us = 0o002,
+    _0_54Us = 0o003,
+    _0_80Us = 0o004,
+    _1_54Us = 0o005,
+    _3_34Us = 0o006,
+    _6_58Us = 0o007,
+}
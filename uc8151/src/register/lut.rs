@@ -0,0 +1,136 @@
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LevelSelection {
+    Gnd = 0b00,
+    Vdh = 0b01,
+    Vdl = 0b10,
+    Vdhr = 0b11,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LutSetting {
+    pub level_select_1: LevelSelection,
+    pub level_select_2: LevelSelection,
+    pub level_select_3: LevelSelection,
+    pub level_select_4: LevelSelection,
+    pub number_of_frames_1: u8,
+    pub number_of_frames_2: u8,
+    pub number_of_frames_3: u8,
+    pub number_of_frames_4: u8,
+    pub times_to_repeat: u8,
+}
+
+impl Default for LutSetting {
+    fn default() -> Self {
+        Self {
+            level_select_1: LevelSelection::Gnd,
+            level_select_2: LevelSelection::Gnd,
+            level_select_3: LevelSelection::Gnd,
+            level_select_4: LevelSelection::Gnd,
+            number_of_frames_1: 0,
+            number_of_frames_2: 0,
+            number_of_frames_3: 0,
+            number_of_frames_4: 0,
+            times_to_repeat: 0,
+        }
+    }
+}
+
+impl From<&LutSetting> for [u8; 6] {
+    fn from(setting: &LutSetting) -> [u8; 6] {
+        [
+            (setting.level_select_1 as u8) << 6
+                | (setting.level_select_2 as u8) << 4
+                | (setting.level_select_3 as u8) << 2
+                | (setting.level_select_4 as u8),
+            setting.number_of_frames_1,
+            setting.number_of_frames_2,
+            setting.number_of_frames_3,
+            setting.number_of_frames_4,
+            setting.times_to_repeat,
+        ]
+    }
+}
+
+impl From<[u8; 6]> for LutSetting {
+    fn from(bytes: [u8; 6]) -> LutSetting {
+        let level = |shift: u8| match bytes[0] >> shift & 0b11 {
+            0b00 => LevelSelection::Gnd,
+            0b01 => LevelSelection::Vdh,
+            0b10 => LevelSelection::Vdl,
+            _ => LevelSelection::Vdhr,
+        };
+        LutSetting {
+            level_select_1: level(6),
+            level_select_2: level(4),
+            level_select_3: level(2),
+            level_select_4: level(0),
+            number_of_frames_1: bytes[1],
+            number_of_frames_2: bytes[2],
+            number_of_frames_3: bytes[3],
+            number_of_frames_4: bytes[4],
+            times_to_repeat: bytes[5],
+        }
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct LutSettingGroup(pub [LutSetting; 7]);
+
+impl From<LutSettingGroup> for [u8; 42] {
+    fn from(group: LutSettingGroup) -> [u8; 42] {
+        let mut bytes = [0u8; 42];
+        for (i, setting) in group.0.iter().enumerate() {
+            let setting_bytes: [u8; 6] = setting.into();
+            bytes[i * 6..(i + 1) * 6].copy_from_slice(&setting_bytes);
+        }
+
+        bytes
+    }
+}
+
+impl From<[u8; 42]> for LutSettingGroup {
+    fn from(bytes: [u8; 42]) -> LutSettingGroup {
+        let mut group = LutSettingGroup::default();
+        for (setting, chunk) in group.0.iter_mut().zip(bytes.chunks(6)) {
+            let mut setting_bytes = [0u8; 6];
+            setting_bytes.copy_from_slice(chunk);
+            *setting = setting_bytes.into();
+        }
+
+        group
+    }
+}
+
+impl From<LutSettingGroup> for [u8; 44] {
+    fn from(group: LutSettingGroup) -> [u8; 44] {
+        let mut bytes = [0u8; 44];
+        for (i, setting) in group.0.iter().enumerate() {
+            let setting_bytes: [u8; 6] = setting.into();
+            bytes[i * 6..(i + 1) * 6].copy_from_slice(&setting_bytes);
+        }
+
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_selections() {
+        let setting = LutSetting {
+            level_select_1: LevelSelection::Vdh,
+            level_select_2: LevelSelection::Vdl,
+            level_select_3: LevelSelection::Gnd,
+            level_select_4: LevelSelection::Vdhr,
+            number_of_frames_1: 1,
+            number_of_frames_2: 2,
+            number_of_frames_3: 3,
+            number_of_frames_4: 4,
+            times_to_repeat: 5,
+        };
+        assert_eq!([0b01_10_00_11, 1, 2, 3, 4, 5], <[u8; 6]>::from(&setting));
+        assert_eq!(setting, LutSetting::from([0b01_10_00_11, 1, 2, 3, 4, 5]));
+    }
+}
@@ -3,14 +3,16 @@ mod booster_soft_start;
 pub mod constant;
 mod lut;
 mod panel_setting;
+mod partial_window;
 mod pll_control;
 mod power_off_sequence_setting;
 mod power_setting;
-mod partial_window;
+mod resolution_setting;
 pub use self::booster_soft_start::*;
+pub use self::lut::*;
 pub use self::panel_setting::*;
+pub use self::partial_window::*;
 pub use self::pll_control::*;
 pub use self::power_off_sequence_setting::*;
 pub use self::power_setting::*;
-pub use self::partial_window::*;
-pub use self::lut::*;
+pub use self::resolution_setting::*;
@@ -0,0 +1,158 @@
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PanelSetting {
+    pub resolution: Resolution,
+    pub lut_selection: LutSelection,
+    pub colour_selection: ColourSelection,
+    pub gate_scan_direction: GateScanDirection,
+    pub source_shift_direction: SourceShiftDirection,
+    pub booster_enable: BoosterEnable,
+    pub soft_reset: SoftReset,
+}
+
+impl Default for PanelSetting {
+    fn default() -> Self {
+        Self {
+            resolution: Resolution::Res96x230,
+            lut_selection: LutSelection::FromOtp,
+            colour_selection: ColourSelection::BlackWhiteRed,
+            gate_scan_direction: GateScanDirection::Down,
+            source_shift_direction: SourceShiftDirection::Right,
+            booster_enable: BoosterEnable::On,
+            soft_reset: SoftReset::None,
+        }
+    }
+}
+
+impl From<PanelSetting> for [u8; 1] {
+    fn from(ps: PanelSetting) -> [u8; 1] {
+        [ps.resolution as u8
+            | ps.lut_selection as u8
+            | ps.colour_selection as u8
+            | ps.gate_scan_direction as u8
+            | ps.source_shift_direction as u8
+            | ps.booster_enable as u8
+            | ps.soft_reset as u8]
+    }
+}
+
+impl From<[u8; 1]> for PanelSetting {
+    fn from(bytes: [u8; 1]) -> PanelSetting {
+        let bit = |mask: u8| bytes[0] & mask != 0;
+        PanelSetting {
+            resolution: match bytes[0] & 0b11_000000 {
+                0b00_000000 => Resolution::Res96x230,
+                0b01_000000 => Resolution::Res96x262,
+                0b10_000000 => Resolution::Res129x296,
+                _ => Resolution::Res160x296,
+            },
+            lut_selection: match bit(LutSelection::FromRegister as u8) {
+                false => LutSelection::FromOtp,
+                true => LutSelection::FromRegister,
+            },
+            colour_selection: match bit(ColourSelection::BlackWhite as u8) {
+                false => ColourSelection::BlackWhiteRed,
+                true => ColourSelection::BlackWhite,
+            },
+            gate_scan_direction: match bit(GateScanDirection::Down as u8) {
+                false => GateScanDirection::Up,
+                true => GateScanDirection::Down,
+            },
+            source_shift_direction: match bit(SourceShiftDirection::Right as u8) {
+                false => SourceShiftDirection::Left,
+                true => SourceShiftDirection::Right,
+            },
+            booster_enable: match bit(BoosterEnable::On as u8) {
+                false => BoosterEnable::Off,
+                true => BoosterEnable::On,
+            },
+            soft_reset: match bit(SoftReset::None as u8) {
+                false => SoftReset::Reset,
+                true => SoftReset::None,
+            },
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Resolution {
+    Res96x230 = 0b00_000000,
+    /// 96x252 in the datasheet.
+    Res96x262 = 0b01_000000,
+    /// 128x296 in the datasheet.
+    Res129x296 = 0b10_000000,
+    Res160x296 = 0b11_000000,
+}
+
+impl Resolution {
+    /// Number of source outputs, i.e. pixels along a gate line.
+    pub fn sources(&self) -> u16 {
+        match self {
+            Self::Res96x230 | Self::Res96x262 => 96,
+            Self::Res129x296 => 128,
+            Self::Res160x296 => 160,
+        }
+    }
+
+    /// Number of gate lines.
+    pub fn gates(&self) -> u16 {
+        match self {
+            Self::Res96x230 => 230,
+            Self::Res96x262 => 252,
+            Self::Res129x296 | Self::Res160x296 => 296,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LutSelection {
+    FromOtp = 0b00_0_00000,
+    FromRegister = 0b00_1_00000,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ColourSelection {
+    BlackWhiteRed = 0b000_0_0000,
+    BlackWhite = 0b000_1_0000,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GateScanDirection {
+    Up = 0b0000_0_000,
+    Down = 0b0000_1_000,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SourceShiftDirection {
+    Left = 0b00000_0_00,
+    Right = 0b00000_1_00,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BoosterEnable {
+    Off = 0b000000_0_0,
+    On = 0b000000_1_0,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SoftReset {
+    Reset = 0b0000000_0,
+    None = 0b0000000_1,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default() {
+        let default_panel_setting = PanelSetting::default();
+        assert_eq!([0x0fu8], <[u8; 1]>::from(default_panel_setting))
+    }
+
+    #[test]
+    fn test_decode() {
+        for byte in [0x0f, 0x1f, 0x3f, 0x93, 0xd4] {
+            assert_eq!([byte], <[u8; 1]>::from(PanelSetting::from([byte])));
+        }
+    }
+}
@@ -0,0 +1,149 @@
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PartialWindow {
+    pub horizontal_start_channel_bank: u8,
+    pub horizontal_end_channel_bank: u8,
+    pub vertical_start_line: u16,
+    pub vertical_end_line: u16,
+    /// Only scan the gates inside the window, rather than both inside and outside of it.
+    pub partial_scan: bool,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PartialWindowError {
+    /// The start bank is after the end bank.
+    BanksReversed { start: u8, end: u8 },
+    /// The start line is after the end line.
+    LinesReversed { start: u16, end: u16 },
+    /// The end bank is past the last bank of the panel.
+    BankOutOfRange { bank: u8, last: u8 },
+    /// The end line is past the last line of the panel.
+    LineOutOfRange { line: u16, last: u16 },
+}
+
+impl PartialWindow {
+    /// Creates a window, checking it is the right way round and fits a panel of `sources` by
+    /// `gates` pixels.
+    pub fn new(
+        horizontal_start_channel_bank: u8,
+        horizontal_end_channel_bank: u8,
+        vertical_start_line: u16,
+        vertical_end_line: u16,
+        partial_scan: bool,
+        sources: u16,
+        gates: u16,
+    ) -> Result<Self, PartialWindowError> {
+        if horizontal_start_channel_bank > horizontal_end_channel_bank {
+            return Err(PartialWindowError::BanksReversed {
+                start: horizontal_start_channel_bank,
+                end: horizontal_end_channel_bank,
+            });
+        }
+        if vertical_start_line > vertical_end_line {
+            return Err(PartialWindowError::LinesReversed {
+                start: vertical_start_line,
+                end: vertical_end_line,
+            });
+        }
+        // The register holds 5 bits of bank and 9 of line.
+        let last_bank = sources.min(32 * 8).div_ceil(8).saturating_sub(1) as u8;
+        if horizontal_end_channel_bank > last_bank {
+            return Err(PartialWindowError::BankOutOfRange {
+                bank: horizontal_end_channel_bank,
+                last: last_bank,
+            });
+        }
+        let last_line = gates.min(512).saturating_sub(1);
+        if vertical_end_line > last_line {
+            return Err(PartialWindowError::LineOutOfRange {
+                line: vertical_end_line,
+                last: last_line,
+            });
+        }
+
+        Ok(Self {
+            horizontal_start_channel_bank,
+            horizontal_end_channel_bank,
+            vertical_start_line,
+            vertical_end_line,
+            partial_scan,
+        })
+    }
+}
+
+impl Default for PartialWindow {
+    fn default() -> Self {
+        Self {
+            horizontal_start_channel_bank: 0,
+            horizontal_end_channel_bank: 0,
+            vertical_start_line: 0,
+            vertical_end_line: 0,
+            partial_scan: false,
+        }
+    }
+}
+
+/// Packs the window into the register, keeping only the bits it has room for. Make windows
+/// with [`PartialWindow::new`] so nothing is lost.
+impl From<PartialWindow> for [u8; 7] {
+    fn from(setting: PartialWindow) -> [u8; 7] {
+        [
+            setting.horizontal_start_channel_bank << 3,
+            setting.horizontal_end_channel_bank << 3 | 0x07,
+            (setting.vertical_start_line >> 8) as u8 & 0x01,
+            (setting.vertical_start_line & 0xff) as u8,
+            (setting.vertical_end_line >> 8) as u8 & 0x01,
+            (setting.vertical_end_line & 0xff) as u8,
+            match setting.partial_scan {
+                true => 0x00,
+                false => 0x01,
+            },
+        ]
+    }
+}
+
+impl From<[u8; 7]> for PartialWindow {
+    fn from(bytes: [u8; 7]) -> PartialWindow {
+        PartialWindow {
+            horizontal_start_channel_bank: bytes[0] >> 3,
+            horizontal_end_channel_bank: bytes[1] >> 3,
+            vertical_start_line: u16::from(bytes[2] & 0x01) << 8 | u16::from(bytes[3]),
+            vertical_end_line: u16::from(bytes[4] & 0x01) << 8 | u16::from(bytes[5]),
+            partial_scan: bytes[6] & 0x01 == 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoding() {
+        let window = PartialWindow::new(2, 5, 10, 295, true, 128, 296).unwrap();
+        assert_eq!(
+            [0x10, 0x2f, 0x00, 0x0a, 0x01, 0x27, 0x00],
+            <[u8; 7]>::from(window)
+        );
+        assert_eq!(window, PartialWindow::from(<[u8; 7]>::from(window)));
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert_eq!(
+            PartialWindow::new(0, 16, 0, 0, false, 128, 296).err(),
+            Some(PartialWindowError::BankOutOfRange { bank: 16, last: 15 })
+        );
+        assert_eq!(
+            PartialWindow::new(0, 0, 5, 4, false, 128, 296).err(),
+            Some(PartialWindowError::LinesReversed { start: 5, end: 4 })
+        );
+        assert!(PartialWindow::new(0, 12, 0, 211, false, 104, 212).is_ok());
+        assert_eq!(
+            PartialWindow::new(0, 0, 0, 212, false, 104, 212).err(),
+            Some(PartialWindowError::LineOutOfRange {
+                line: 212,
+                last: 211
+            })
+        );
+    }
+}
@@ -1,3 +1,4 @@
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum PowerOffSequence {
     Frame1 = 0b00_0000,
     Frame2 = 0b01_0000,
@@ -16,3 +17,14 @@ impl From<PowerOffSequence> for [u8; 1] {
         [sequence as u8]
     }
 }
+
+impl From<[u8; 1]> for PowerOffSequence {
+    fn from(bytes: [u8; 1]) -> PowerOffSequence {
+        match bytes[0] & 0b11_0000 {
+            0b00_0000 => Self::Frame1,
+            0b01_0000 => Self::Frame2,
+            0b10_0000 => Self::Frame3,
+            _ => Self::Frame4,
+        }
+    }
+}
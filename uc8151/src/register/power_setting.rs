@@ -1,3 +1,6 @@
+use core::convert::TryFrom;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct PowerSetting {
     pub source_power: PowerSelection,
     pub gate_power: PowerSelection,
@@ -34,11 +37,42 @@ impl From<PowerSetting> for [u8; 5] {
     }
 }
 
+/// Decodes a setting, failing with the first voltage byte that isn't a voltage.
+impl TryFrom<[u8; 5]> for PowerSetting {
+    type Error = u8;
+
+    fn try_from(bytes: [u8; 5]) -> Result<PowerSetting, u8> {
+        let selection = |mask: u8| match bytes[0] & mask != 0 {
+            false => PowerSelection::External,
+            true => PowerSelection::Internal,
+        };
+        Ok(PowerSetting {
+            source_power: selection(0b10),
+            gate_power: selection(0b01),
+            vcom_voltage: match bytes[1] & VcomVoltage::Vgh as u8 != 0 {
+                false => VcomVoltage::Vdh,
+                true => VcomVoltage::Vgh,
+            },
+            vgh_vgl_voltage: match bytes[1] & 0b11 {
+                0b00 => VghVglVoltage::V16,
+                0b01 => VghVglVoltage::V15,
+                0b10 => VghVglVoltage::V14,
+                _ => VghVglVoltage::V13,
+            },
+            internal_vdh_voltage: InternalVoltage::try_from(bytes[2])?,
+            internal_vdl_voltage: InternalVoltage::try_from(bytes[3])?,
+            internal_vdhr_voltage: InternalVoltage::try_from(bytes[4])?,
+        })
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum PowerSelection {
     External = 0,
     Internal = 1,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum VcomVoltage {
     /// VCOMH=VDH+DC-VCOM
     Vdh = 0b00000_0_00,
@@ -46,6 +80,7 @@ pub enum VcomVoltage {
     Vgh = 0b00000_1_00,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum VghVglVoltage {
     /// VGH=16V, VGL= -16V
     V16 = 0b000000_00,
@@ -54,6 +89,7 @@ pub enum VghVglVoltage {
     V13 = 0b000000_11,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum InternalVoltage {
     V2_4 = 0b000000,
     V2_6 = 0b000001,
@@ -109,3 +145,28 @@ pub enum InternalVoltage {
 
     V11_0 = 0b101011,
 }
+
+impl InternalVoltage {
+    /// Every voltage, in order, so each one's index is its code.
+    const ALL: [InternalVoltage; 44] = {
+        use InternalVoltage::*;
+        [
+            V2_4, V2_6, V2_8, V3_0, V3_2, V3_4, V3_6, V3_8, V4_0, V4_2, V4_4, V4_6, V4_8, V5_0,
+            V5_2, V5_4, V5_6, V5_8, V6_0, V6_2, V6_4, V6_6, V6_8, V7_0, V7_2, V7_4, V7_6, V7_8,
+            V8_0, V8_2, V8_4, V8_6, V8_8, V9_0, V9_2, V9_4, V9_6, V9_8, V10_0, V10_2, V10_4, V10_6,
+            V10_8, V11_0,
+        ]
+    };
+}
+
+/// Decodes a voltage, failing with the byte if it isn't one.
+impl TryFrom<u8> for InternalVoltage {
+    type Error = u8;
+
+    fn try_from(code: u8) -> Result<InternalVoltage, u8> {
+        InternalVoltage::ALL
+            .get(usize::from(code))
+            .copied()
+            .ok_or(code)
+    }
+}
@@ -0,0 +1,42 @@
+/// Overrides the resolution selected in the panel setting.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ResolutionSetting {
+    /// Number of source outputs, rounded down to a multiple of 8.
+    pub horizontal: u8,
+    /// Number of gate lines, up to 296.
+    pub vertical: u16,
+}
+
+impl From<ResolutionSetting> for [u8; 3] {
+    fn from(setting: ResolutionSetting) -> [u8; 3] {
+        [
+            setting.horizontal & 0xf8,
+            (setting.vertical >> 8) as u8 & 0x01,
+            (setting.vertical & 0xff) as u8,
+        ]
+    }
+}
+
+impl From<[u8; 3]> for ResolutionSetting {
+    fn from(bytes: [u8; 3]) -> ResolutionSetting {
+        ResolutionSetting {
+            horizontal: bytes[0] & 0xf8,
+            vertical: u16::from(bytes[1] & 0x01) << 8 | u16::from(bytes[2]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoding() {
+        let setting = ResolutionSetting {
+            horizontal: 104,
+            vertical: 296,
+        };
+        assert_eq!([104, 0x01, 0x28], <[u8; 3]>::from(setting));
+        assert_eq!(setting, ResolutionSetting::from([104, 0x01, 0x28]));
+    }
+}
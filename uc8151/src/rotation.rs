@@ -0,0 +1,76 @@
+//! Display orientation.
+//!
+//! Half turns are done by the controller, by reversing the gate scan and source shift
+//! directions in the panel setting. Quarter turns additionally swap the axes in software, so
+//! framebuffers and regions take coordinates in the rotated frame and map them onto the panel's
+//! own source/gate grid.
+
+use super::region::Region;
+use super::register::*;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Rotation {
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+impl Default for Rotation {
+    fn default() -> Self {
+        Self::Deg0
+    }
+}
+
+impl Rotation {
+    fn flipped(self) -> bool {
+        matches!(self, Self::Deg180 | Self::Deg270)
+    }
+
+    pub fn swaps_axes(self) -> bool {
+        matches!(self, Self::Deg90 | Self::Deg270)
+    }
+
+    pub fn gate_scan_direction(self) -> GateScanDirection {
+        match self.flipped() {
+            false => GateScanDirection::Down,
+            true => GateScanDirection::Up,
+        }
+    }
+
+    pub fn source_shift_direction(self) -> SourceShiftDirection {
+        match self.flipped() {
+            false => SourceShiftDirection::Right,
+            true => SourceShiftDirection::Left,
+        }
+    }
+
+    /// Size of a `width` by `height` panel as seen in this rotation.
+    pub fn size(self, width: usize, height: usize) -> (usize, usize) {
+        match self.swaps_axes() {
+            false => (width, height),
+            true => (height, width),
+        }
+    }
+
+    /// Maps a point in rotated coordinates onto a panel `width` pixels across.
+    pub fn to_panel(self, x: usize, y: usize, width: usize) -> (usize, usize) {
+        match self.swaps_axes() {
+            false => (x, y),
+            true => (width - 1 - y, x),
+        }
+    }
+
+    /// Maps a region in rotated coordinates onto a panel `width` pixels across.
+    pub fn region_to_panel(self, region: Region, width: u16) -> Region {
+        match self.swaps_axes() {
+            false => region,
+            true => Region {
+                x: width - (region.y + region.height),
+                y: region.x,
+                width: region.height,
+                height: region.width,
+            },
+        }
+    }
+}
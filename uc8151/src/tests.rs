@@ -0,0 +1,380 @@
+//! Checks exactly what each driver method puts on the wire, against [`mock`](super::mock).
+
+use std::vec::Vec;
+
+use super::luts::{self, Luts};
+use super::mock::{Display, Mock, Transaction, Transaction::*};
+use super::profile::{
+    PanelProfile, PANEL_2IN13_104X212, PANEL_2IN9_128X296, PANEL_2IN9_128X296_RED,
+};
+use super::register::*;
+use super::rotation::Rotation;
+
+/// Runs `f` on a driver for `profile`, checking it makes exactly the `expected` transactions.
+fn check_with(profile: PanelProfile, expected: Vec<Transaction>, f: impl FnOnce(&mut Display)) {
+    let mock = Mock::new(expected);
+    f(&mut mock.display(profile));
+    mock.done();
+}
+
+fn check(expected: Vec<Transaction>, f: impl FnOnce(&mut Display)) {
+    check_with(PANEL_2IN9_128X296, expected, f)
+}
+
+/// A command and its data, framed the way the controller expects.
+fn command(command: u8, data: &[u8]) -> Vec<Transaction> {
+    let mut transactions = vec![Release, Cs(false), Dc(false), Write(vec![command])];
+    if !data.is_empty() {
+        transactions.extend([Dc(true), Write(data.to_vec())]);
+    }
+    transactions.push(Cs(true));
+    transactions
+}
+
+/// A command followed by `bytes` clocked back in from the controller.
+fn command_read(command: u8, bytes: &[u8]) -> Vec<Transaction> {
+    let mut transactions = vec![
+        Release,
+        Cs(false),
+        Dc(false),
+        Write(vec![command]),
+        Acquire,
+        Dc(true),
+    ];
+    for &byte in bytes {
+        for bit in (0..8).rev() {
+            transactions.extend([Clock(true), Data(byte >> bit & 1 != 0), Clock(false)]);
+        }
+    }
+    transactions.push(Cs(true));
+    transactions
+}
+
+/// BUSY reading low `polls` times before the controller is done.
+fn busy_wait(polls: usize) -> Vec<Transaction> {
+    let mut transactions = vec![Busy(false); polls];
+    transactions.push(Busy(true));
+    transactions
+}
+
+fn reset() -> Vec<Transaction> {
+    [
+        vec![Reset(false), Delay(10), Reset(true), Delay(10)],
+        busy_wait(0),
+    ]
+    .concat()
+}
+
+fn load_luts(luts: &Luts) -> Vec<Transaction> {
+    [
+        command(0x20, &luts.vcom),
+        command(0x21, &luts.w2w),
+        command(0x22, &luts.b2w),
+        command(0x23, &luts.w2b),
+        command(0x24, &luts.b2b),
+        command(0x30, &[luts.pll as u8]),
+        busy_wait(0),
+    ]
+    .concat()
+}
+
+#[test]
+fn test_command_framing() {
+    check(
+        vec![
+            Release,
+            Cs(false),
+            Dc(false),
+            Write(vec![0x50]),
+            Dc(true),
+            Write(vec![0x97]),
+            Cs(true),
+        ],
+        |display| display.command(0x50, [0x97]),
+    );
+    check(
+        vec![Release, Cs(false), Dc(false), Write(vec![0x02]), Cs(true)],
+        |display| display.command(0x02, []),
+    );
+}
+
+#[test]
+fn test_command_chunks() {
+    check(
+        [
+            vec![Release, Cs(false), Dc(false), Write(vec![0x13])],
+            vec![Dc(true), Write(vec![1, 2, 3, 4, 5]), Cs(true)],
+        ]
+        .concat(),
+        |display| display.command_chunks(0x13, [&[1, 2][..], &[3, 4, 5][..]]),
+    );
+}
+
+#[test]
+fn test_data() {
+    let expected = vec![Release, Cs(false), Dc(true), Write(vec![7, 8, 9]), Cs(true)];
+    check(expected.clone(), |display| display.data([7, 8, 9]));
+    check(expected, |display| display.data_slice(&[7, 8, 9]));
+}
+
+#[test]
+fn test_data_slice_with_leftover_bytes() {
+    // Bytes already in the receive FIFO mustn't be counted against the ones sent.
+    let mock = Mock::new(vec![
+        Release,
+        Cs(false),
+        Dc(true),
+        Write(vec![1, 2, 3]),
+        Cs(true),
+    ]);
+    mock.receive(5);
+    mock.display(PANEL_2IN9_128X296).data_slice(&[1, 2, 3]);
+    mock.done();
+}
+
+#[test]
+fn test_busy_wait() {
+    check(busy_wait(0), |display| display.busy_wait());
+    check(busy_wait(3), |display| display.busy_wait());
+}
+
+#[test]
+fn test_reset() {
+    check(
+        [
+            vec![Reset(false), Delay(10), Reset(true), Delay(10)],
+            busy_wait(2),
+        ]
+        .concat(),
+        |display| display.reset(),
+    );
+}
+
+#[test]
+fn test_setup() {
+    let profile = PANEL_2IN9_128X296;
+    let power_setting: [u8; 5] = profile.power_setting.into();
+    let booster_soft_start: [u8; 3] = profile.booster_soft_start.into();
+    check(
+        [
+            reset(),
+            // 128x296, LUTs from registers, black/white, scanning down and right, booster on
+            command(0x00, &[0b10_1_1_1_1_1_1]),
+            load_luts(&luts::DEFAULT),
+            command(0x01, &power_setting),
+            command(0x04, &[]),
+            busy_wait(1),
+            command(0x06, &booster_soft_start),
+            command(0x03, &[0x00]),
+            command(0x41, &[0x00]),
+            command(0x60, &[0x22]),
+            command(0x50, &[0b01_00_1100]),
+        ]
+        .concat(),
+        |display| display.setup(),
+    );
+}
+
+#[test]
+fn test_setup_without_panel_resolution() {
+    // Tri-colour panels use the LUTs in OTP, and 104x212 needs the resolution setting.
+    let profile = PANEL_2IN13_104X212;
+    let power_setting: [u8; 5] = profile.power_setting.into();
+    let booster_soft_start: [u8; 3] = profile.booster_soft_start.into();
+    check_with(
+        profile,
+        [
+            reset(),
+            command(0x00, &[0b11_0_0_1_1_1_1]),
+            command(0x61, &[104, 0, 212]),
+            command(0x01, &power_setting),
+            command(0x04, &[]),
+            busy_wait(0),
+            command(0x06, &booster_soft_start),
+            command(0x03, &[0x00]),
+            command(0x41, &[0x00]),
+            command(0x60, &[0x22]),
+            command(0x50, &[0b01_00_1100]),
+        ]
+        .concat(),
+        |display| {
+            display.set_colour_selection(ColourSelection::BlackWhiteRed);
+            display.setup()
+        },
+    );
+}
+
+#[test]
+fn test_setup_with_tri_colour_luts() {
+    let profile = PanelProfile {
+        tri_colour_luts: Some(&luts::TURBO),
+        ..PANEL_2IN9_128X296_RED
+    };
+    let power_setting: [u8; 5] = profile.power_setting.into();
+    let booster_soft_start: [u8; 3] = profile.booster_soft_start.into();
+    check_with(
+        profile,
+        [
+            reset(),
+            // 128x296, LUTs from registers, black/white/red, scanning down and right, booster on
+            command(0x00, &[0b10_1_0_1_1_1_1]),
+            load_luts(&luts::TURBO),
+            command(0x01, &power_setting),
+            command(0x04, &[]),
+            busy_wait(0),
+            command(0x06, &booster_soft_start),
+            command(0x03, &[0x00]),
+            command(0x41, &[0x00]),
+            command(0x60, &[0x22]),
+            command(0x50, &[0b01_00_1100]),
+        ]
+        .concat(),
+        |display| display.setup(),
+    );
+}
+
+#[test]
+fn test_set_rotation() {
+    check(command(0x00, &[0b10_1_1_0_0_1_1]), |display| {
+        display.set_rotation(Rotation::Deg180)
+    });
+}
+
+#[test]
+fn test_power_on() {
+    check([command(0x04, &[]), busy_wait(2)].concat(), |display| {
+        display.power_on(true)
+    });
+    check(command(0x04, &[]), |display| display.power_on(false));
+}
+
+#[test]
+fn test_power_off() {
+    check(command(0x02, &[]), |display| display.power_off());
+}
+
+#[test]
+fn test_deep_sleep() {
+    check(command(0x07, &[0xa5]), |display| display.deep_sleep());
+}
+
+#[test]
+fn test_display_refresh() {
+    check([command(0x12, &[]), busy_wait(4)].concat(), |display| {
+        display.display_refresh(true)
+    });
+    check(command(0x12, &[]), |display| display.display_refresh(false));
+}
+
+#[test]
+fn test_partial_window() {
+    check(
+        command(0x90, &[0x10, 0x2f, 0x00, 0x0a, 0x01, 0x27, 0x00]),
+        |display| {
+            let window = PartialWindow::new(2, 5, 10, 295, true, 128, 296).unwrap();
+            display.partial_window(window).unwrap()
+        },
+    );
+}
+
+#[test]
+fn test_partial_window_off_the_panel() {
+    // Windows made without `PartialWindow::new` are still checked, and nothing is sent.
+    let mut result = None;
+    check(vec![], |display| {
+        result = Some(display.partial_window(PartialWindow {
+            horizontal_start_channel_bank: 0,
+            horizontal_end_channel_bank: 16,
+            vertical_start_line: 0,
+            vertical_end_line: 295,
+            partial_scan: false,
+        }))
+    });
+    assert_eq!(
+        Some(Err(PartialWindowError::BankOutOfRange {
+            bank: 16,
+            last: 15
+        })),
+        result
+    );
+}
+
+#[test]
+fn test_partial_in_out() {
+    check(command(0x91, &[]), |display| display.partial_in());
+    check(command(0x92, &[]), |display| display.partial_out());
+}
+
+#[test]
+fn test_data_stop() {
+    let mut stopped = None;
+    check(command_read(0x11, &[0x80]), |display| {
+        stopped = Some(display.data_stop())
+    });
+    assert_eq!(Some(true), stopped);
+
+    check(command_read(0x11, &[0x00]), |display| {
+        stopped = Some(display.data_stop())
+    });
+    assert_eq!(Some(false), stopped);
+}
+
+#[test]
+fn test_command_read() {
+    // Every bit of every byte is clocked in, and the buffer's old contents don't show through.
+    let mut buffer = [0xff; 3];
+    check(command_read(0x70, &[0xa5, 0x00, 0x3c]), |display| {
+        display.command_read(0x70, &mut buffer)
+    });
+    assert_eq!([0xa5, 0x00, 0x3c], buffer);
+}
+
+type LutSetter = fn(&mut Display, LutSettingGroup);
+
+#[test]
+fn test_lut_setters() {
+    // The VCOM LUT has two more bytes than the others, which are sent as zeros.
+    let setters: [(u8, LutSetter, usize); 5] = [
+        (0x20, Display::vcom_lut, 2),
+        (0x21, Display::w2w_lut, 0),
+        (0x22, Display::b2w_lut, 0),
+        (0x23, Display::w2b_lut, 0),
+        (0x24, Display::b2b_lut, 0),
+    ];
+    for (opcode, setter, padding) in setters {
+        let mut bytes = luts::TURBO.w2b.to_vec();
+        bytes.resize(bytes.len() + padding, 0);
+        check(command(opcode, &bytes), |display| {
+            setter(display, luts::TURBO.w2b.into())
+        });
+    }
+}
+
+#[test]
+fn test_pll_control() {
+    check(command(0x30, &[0o72]), |display| {
+        display.pll_control(PllClockFrequency::_100Hz)
+    });
+}
+
+#[test]
+fn test_load_luts() {
+    check(load_luts(&luts::TURBO), |display| display.turbo_luts());
+}
+
+#[test]
+fn test_register_settings() {
+    let power_setting: [u8; 5] = PANEL_2IN9_128X296.power_setting.into();
+    check(command(0x01, &power_setting), |display| {
+        display.power_setting(PANEL_2IN9_128X296.power_setting)
+    });
+    check(command(0x03, &[0x00]), |display| {
+        display.power_off_sequence_setting(PowerOffSequence::Frame1)
+    });
+    check(command(0x61, &[104, 0x01, 0x28]), |display| {
+        display.resolution_setting(ResolutionSetting {
+            horizontal: 104,
+            vertical: 296,
+        })
+    });
+}
@@ -0,0 +1,292 @@
+//! Recording what the driver does on the wire.
+//!
+//! The driver reports every CS and DC change, byte and BUSY wait to a [`Trace`]. [`NoTrace`]
+//! throws them away and costs nothing; [`TraceBuffer`] packs them into a compact binary log that
+//! can be dumped from the badge and read back on the host with [`records`].
+//!
+//! Each record is a tag byte followed by its payload:
+//!
+//! | Tag   | Payload                     | Meaning                          |
+//! |-------|-----------------------------|----------------------------------|
+//! | `'S'` |                             | CS low                           |
+//! | `'U'` |                             | CS high                          |
+//! | `'X'` |                             | Hardware reset                   |
+//! | `'C'` | command                     | Byte sent with DC low            |
+//! | `'D'` | length, then that many bytes| Bytes sent with DC high          |
+//! | `'R'` | length, then that many bytes| Bytes read back                  |
+//! | `'B'` | microseconds, u32 LE        | Time BUSY was held low           |
+
+const TAG_SELECT: u8 = b'S';
+const TAG_DESELECT: u8 = b'U';
+const TAG_RESET: u8 = b'X';
+const TAG_COMMAND: u8 = b'C';
+const TAG_DATA: u8 = b'D';
+const TAG_READ: u8 = b'R';
+const TAG_BUSY: u8 = b'B';
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Event {
+    /// CS went low.
+    Select,
+    /// CS went high.
+    Deselect,
+    Reset,
+    /// A byte sent with DC low.
+    Command(u8),
+    /// A byte sent with DC high.
+    Data(u8),
+    /// A byte read back from the controller.
+    Read(u8),
+    /// The controller was found busy, and the driver started waiting for it.
+    BusyStart,
+    BusyEnd,
+}
+
+pub trait Trace {
+    fn record(&mut self, event: Event);
+}
+
+/// Records nothing.
+pub struct NoTrace;
+
+impl Trace for NoTrace {
+    fn record(&mut self, _event: Event) {}
+}
+
+/// Packs events into a fixed buffer of `N` bytes, in the format described in the [module
+/// documentation](self). Once the buffer is full, later events are dropped.
+///
+/// `clock` gives the time in microseconds, and is only used to time BUSY waits.
+pub struct TraceBuffer<C, const N: usize> {
+    buffer: [u8; N],
+    len: usize,
+    /// Where the length of the last record is, if it is a data or read record that can be
+    /// extended.
+    run: Option<(u8, usize)>,
+    overflowed: bool,
+    clock: C,
+    busy_since: Option<u32>,
+}
+
+impl<C: FnMut() -> u32, const N: usize> TraceBuffer<C, N> {
+    pub fn new(clock: C) -> Self {
+        Self {
+            buffer: [0; N],
+            len: 0,
+            run: None,
+            overflowed: false,
+            clock,
+            busy_since: None,
+        }
+    }
+
+    /// The records so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+
+    /// Whether any events had to be dropped for lack of space.
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.run = None;
+        self.overflowed = false;
+    }
+
+    fn push(&mut self, bytes: &[u8]) -> bool {
+        if self.overflowed || self.len + bytes.len() > N {
+            self.overflowed = true;
+            return false;
+        }
+        self.buffer[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+        true
+    }
+
+    fn push_record(&mut self, bytes: &[u8]) {
+        self.run = None;
+        self.push(bytes);
+    }
+
+    /// Adds `byte` to the last record if it is a `tag` record with room, or starts a new one.
+    fn push_run(&mut self, tag: u8, byte: u8) {
+        if let Some((run_tag, at)) = self.run {
+            if run_tag == tag && self.buffer[at] < u8::MAX && self.push(&[byte]) {
+                self.buffer[at] += 1;
+                return;
+            }
+        }
+        if self.push(&[tag, 1, byte]) {
+            self.run = Some((tag, self.len - 2));
+        }
+    }
+}
+
+impl<C: FnMut() -> u32, const N: usize> Trace for TraceBuffer<C, N> {
+    fn record(&mut self, event: Event) {
+        match event {
+            Event::Select => self.push_record(&[TAG_SELECT]),
+            Event::Deselect => self.push_record(&[TAG_DESELECT]),
+            Event::Reset => self.push_record(&[TAG_RESET]),
+            Event::Command(command) => self.push_record(&[TAG_COMMAND, command]),
+            Event::Data(byte) => self.push_run(TAG_DATA, byte),
+            Event::Read(byte) => self.push_run(TAG_READ, byte),
+            Event::BusyStart => self.busy_since = Some((self.clock)()),
+            Event::BusyEnd => {
+                let since = self.busy_since.take().unwrap_or(0);
+                let us = ((self.clock)().wrapping_sub(since)).to_le_bytes();
+                self.push_record(&[TAG_BUSY, us[0], us[1], us[2], us[3]]);
+            }
+        }
+    }
+}
+
+/// A record read back out of a trace. Consecutive data or read bytes come out together.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Record<'a> {
+    Select,
+    Deselect,
+    Reset,
+    Command(u8),
+    Data(&'a [u8]),
+    Read(&'a [u8]),
+    Busy { us: u32 },
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TraceError {
+    /// The byte at `offset` isn't a record tag.
+    UnknownTag { offset: usize, tag: u8 },
+    /// The trace ends part way through a record.
+    Truncated,
+}
+
+/// Reads the records out of a trace made by [`TraceBuffer`].
+pub fn records(trace: &[u8]) -> Records<'_> {
+    Records { trace, offset: 0 }
+}
+
+pub struct Records<'a> {
+    trace: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Records<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], TraceError> {
+        let bytes = self
+            .trace
+            .get(self.offset..self.offset + len)
+            .ok_or(TraceError::Truncated)?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn next_record(&mut self) -> Result<Record<'a>, TraceError> {
+        let offset = self.offset;
+        let tag = self.take(1)?[0];
+        Ok(match tag {
+            TAG_SELECT => Record::Select,
+            TAG_DESELECT => Record::Deselect,
+            TAG_RESET => Record::Reset,
+            TAG_COMMAND => Record::Command(self.take(1)?[0]),
+            TAG_DATA | TAG_READ => {
+                let len = self.take(1)?[0];
+                let bytes = self.take(len.into())?;
+                match tag {
+                    TAG_DATA => Record::Data(bytes),
+                    _ => Record::Read(bytes),
+                }
+            }
+            TAG_BUSY => {
+                let us = self.take(4)?;
+                Record::Busy {
+                    us: u32::from_le_bytes([us[0], us[1], us[2], us[3]]),
+                }
+            }
+            tag => return Err(TraceError::UnknownTag { offset, tag }),
+        })
+    }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Result<Record<'a>, TraceError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.trace.len() {
+            return None;
+        }
+        let record = self.next_record();
+        if record.is_err() {
+            // Nothing after a bad record can be trusted.
+            self.offset = self.trace.len();
+        }
+        Some(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut time = 0;
+        let mut buffer = TraceBuffer::<_, 64>::new(|| {
+            time += 250;
+            time
+        });
+        for event in [
+            Event::Select,
+            Event::Command(0x10),
+            Event::Data(0xaa),
+            Event::Data(0x55),
+            Event::Deselect,
+            Event::BusyStart,
+            Event::BusyEnd,
+            Event::Read(0x80),
+        ] {
+            buffer.record(event);
+        }
+
+        let records: Result<Vec<_>, _> = records(buffer.as_bytes()).collect();
+        assert_eq!(
+            Ok(vec![
+                Record::Select,
+                Record::Command(0x10),
+                Record::Data(&[0xaa, 0x55]),
+                Record::Deselect,
+                Record::Busy { us: 250 },
+                Record::Read(&[0x80]),
+            ]),
+            records
+        );
+        assert!(!buffer.overflowed());
+    }
+
+    #[test]
+    fn test_long_runs_and_overflow() {
+        let mut buffer = TraceBuffer::<_, 300>::new(|| 0);
+        for byte in 0..=255 {
+            buffer.record(Event::Data(byte));
+        }
+        let lengths: Vec<_> = records(buffer.as_bytes())
+            .map(|record| match record {
+                Ok(Record::Data(bytes)) => bytes.len(),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(vec![255, 1], lengths);
+
+        for _ in 0..25 {
+            buffer.record(Event::Command(0x12));
+        }
+        assert!(buffer.overflowed());
+        assert_eq!(
+            Some(Err(TraceError::Truncated)),
+            records(&buffer.as_bytes()[..2]).next()
+        );
+    }
+}
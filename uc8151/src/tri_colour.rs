@@ -0,0 +1,104 @@
+//! Black/white/red images for tri-colour panels.
+//!
+//! In `ColourSelection::BlackWhiteRed` mode DTM1 carries the black/white plane and DTM2 the red
+//! plane, with a set bit meaning black or red respectively. Red wins if both bits are set.
+
+use super::framebuffer::{Colour, Framebuffer};
+use super::rotation::Rotation;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TriColour {
+    White,
+    Black,
+    Red,
+}
+
+impl TriColour {
+    /// The bits for the black and red planes, as set/unset colours.
+    pub(crate) fn bits(self) -> (Colour, Colour) {
+        match self {
+            Self::White => (Colour::White, Colour::White),
+            Self::Black => (Colour::Black, Colour::White),
+            Self::Red => (Colour::White, Colour::Black),
+        }
+    }
+}
+
+/// A black/white/red image stored as the two bit-planes the controller expects.
+///
+/// The size is given in 8-pixel channel banks and gate lines, as for [`Framebuffer`].
+#[derive(Clone)]
+pub struct TriColourFramebuffer<const BANKS: usize, const LINES: usize> {
+    black: Framebuffer<BANKS, LINES>,
+    red: Framebuffer<BANKS, LINES>,
+}
+
+impl<const BANKS: usize, const LINES: usize> TriColourFramebuffer<BANKS, LINES> {
+    pub const fn new() -> Self {
+        Self {
+            black: Framebuffer::new(),
+            red: Framebuffer::new(),
+        }
+    }
+
+    pub fn rotation(&self) -> Rotation {
+        self.black.rotation()
+    }
+
+    /// Sets the rotation used to map pixel coordinates onto the panel. This does not move what
+    /// has already been drawn.
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.black.set_rotation(rotation);
+        self.red.set_rotation(rotation);
+    }
+
+    /// Width in the current rotation.
+    pub fn width(&self) -> usize {
+        self.black.width()
+    }
+
+    /// Height in the current rotation.
+    pub fn height(&self) -> usize {
+        self.black.height()
+    }
+
+    pub fn fill(&mut self, colour: TriColour) {
+        let (black, red) = colour.bits();
+        self.black.fill(black);
+        self.red.fill(red);
+    }
+
+    /// Sets a pixel, ignoring coordinates outside the framebuffer.
+    pub fn set_pixel(&mut self, x: usize, y: usize, colour: TriColour) {
+        let (black, red) = colour.bits();
+        // Nothing reads the planes' dirty regions, so don't spend time keeping them.
+        self.black.write_pixel(x, y, black);
+        self.red.write_pixel(x, y, red);
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> Option<TriColour> {
+        if self.red.get_pixel(x, y)? == Colour::Black {
+            Some(TriColour::Red)
+        } else if self.black.get_pixel(x, y)? == Colour::Black {
+            Some(TriColour::Black)
+        } else {
+            Some(TriColour::White)
+        }
+    }
+
+    /// Bytes for DTM1.
+    pub fn black_plane(&self) -> impl Iterator<Item = u8> + '_ {
+        self.black.as_bytes().iter().copied()
+    }
+
+    /// Bytes for DTM2.
+    pub fn red_plane(&self) -> impl Iterator<Item = u8> + '_ {
+        self.red.as_bytes().iter().copied()
+    }
+}
+
+impl<const BANKS: usize, const LINES: usize> Default for TriColourFramebuffer<BANKS, LINES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
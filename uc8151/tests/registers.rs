@@ -0,0 +1,635 @@
+//! Property tests for the register encodings.
+//!
+//! Every setting has to survive being encoded and decoded again, and every field of a register
+//! has to land in the bits the datasheet gives it without touching any other field's bits.
+
+use std::convert::TryFrom;
+
+use proptest::prelude::*;
+use proptest::sample::select;
+use uc8151::register::*;
+
+/// A field of a register: the bits the datasheet puts it in, and how to copy it from one
+/// setting to another.
+struct Field<T, const N: usize> {
+    name: &'static str,
+    mask: [u8; N],
+    copy: fn(&mut T, &T),
+}
+
+/// Checks that no two fields share a bit, and that copying each field of `b` into `a` changes
+/// exactly that field's bits of `a`'s encoding to `b`'s.
+fn check_fields<T: Clone, const N: usize>(
+    fields: &[Field<T, N>],
+    encode: impl Fn(&T) -> [u8; N],
+    a: &T,
+    b: &T,
+) -> Result<(), TestCaseError> {
+    for (i, first) in fields.iter().enumerate() {
+        for second in &fields[i + 1..] {
+            let overlap = (0..N).any(|byte| first.mask[byte] & second.mask[byte] != 0);
+            prop_assert!(!overlap, "{} overlaps {}", first.name, second.name);
+        }
+    }
+
+    let (a_bytes, b_bytes) = (encode(a), encode(b));
+    for field in fields {
+        let mut mixed = a.clone();
+        (field.copy)(&mut mixed, b);
+        let expected = zip(
+            and(a_bytes, zip(field.mask, field.mask, |mask, _| !mask)),
+            and(b_bytes, field.mask),
+            |a, b| a | b,
+        );
+        prop_assert_eq!(expected, encode(&mixed), "copying {}", field.name);
+    }
+    Ok(())
+}
+
+/// Combines two encodings byte by byte.
+fn zip<const N: usize>(a: [u8; N], b: [u8; N], f: impl Fn(u8, u8) -> u8) -> [u8; N] {
+    let mut bytes = a;
+    for (byte, b) in bytes.iter_mut().zip(b.iter()) {
+        *byte = f(*byte, *b);
+    }
+    bytes
+}
+
+/// The bits that belong to any of the fields.
+fn mask<T, const N: usize>(fields: &[Field<T, N>]) -> [u8; N] {
+    fields
+        .iter()
+        .fold([0; N], |mask, field| zip(mask, field.mask, |a, b| a | b))
+}
+
+fn and<const N: usize>(bytes: [u8; N], mask: [u8; N]) -> [u8; N] {
+    zip(bytes, mask, |a, b| a & b)
+}
+
+fn panel_setting() -> impl Strategy<Value = PanelSetting> {
+    use ColourSelection::*;
+    use GateScanDirection::*;
+    use LutSelection::*;
+    use Resolution::*;
+    use SourceShiftDirection::*;
+    (
+        select(vec![Res96x230, Res96x262, Res129x296, Res160x296]),
+        select(vec![FromOtp, FromRegister]),
+        select(vec![BlackWhiteRed, BlackWhite]),
+        select(vec![Up, Down]),
+        select(vec![Left, Right]),
+        select(vec![BoosterEnable::Off, BoosterEnable::On]),
+        select(vec![SoftReset::Reset, SoftReset::None]),
+    )
+        .prop_map(
+            |(
+                resolution,
+                lut_selection,
+                colour_selection,
+                gate_scan_direction,
+                source_shift_direction,
+                booster_enable,
+                soft_reset,
+            )| PanelSetting {
+                resolution,
+                lut_selection,
+                colour_selection,
+                gate_scan_direction,
+                source_shift_direction,
+                booster_enable,
+                soft_reset,
+            },
+        )
+}
+
+const PANEL_SETTING_FIELDS: [Field<PanelSetting, 1>; 7] = [
+    Field {
+        name: "resolution",
+        mask: [0b1100_0000],
+        copy: |a, b| a.resolution = b.resolution,
+    },
+    Field {
+        name: "lut_selection",
+        mask: [0b0010_0000],
+        copy: |a, b| a.lut_selection = b.lut_selection,
+    },
+    Field {
+        name: "colour_selection",
+        mask: [0b0001_0000],
+        copy: |a, b| a.colour_selection = b.colour_selection,
+    },
+    Field {
+        name: "gate_scan_direction",
+        mask: [0b0000_1000],
+        copy: |a, b| a.gate_scan_direction = b.gate_scan_direction,
+    },
+    Field {
+        name: "source_shift_direction",
+        mask: [0b0000_0100],
+        copy: |a, b| a.source_shift_direction = b.source_shift_direction,
+    },
+    Field {
+        name: "booster_enable",
+        mask: [0b0000_0010],
+        copy: |a, b| a.booster_enable = b.booster_enable,
+    },
+    Field {
+        name: "soft_reset",
+        mask: [0b0000_0001],
+        copy: |a, b| a.soft_reset = b.soft_reset,
+    },
+];
+
+fn booster_phase_setting() -> impl Strategy<Value = BoosterPhaseSetting> {
+    use DrivingStrength::*;
+    use MinimumOffTime::*;
+    use SoftStartPeriod::*;
+    (
+        select(vec![_10Ms, _20Ms, _30Ms, _40Ms]),
+        select(vec![
+            Strength1, Strength2, Strength3, Strength4, Strength5, Strength6, Strength7, Strength8,
+        ]),
+        select(vec![
+            _0_27Us, _0_34Us, _0_40Us, _0_54Us, _0_80Us, _1_54Us, _3_34Us, _6_58Us,
+        ]),
+    )
+        .prop_map(|(soft_start_period, driving_strength, minimum_off_time)| {
+            BoosterPhaseSetting {
+                soft_start_period,
+                driving_strength,
+                minimum_off_time,
+            }
+        })
+}
+
+const BOOSTER_PHASE_FIELDS: [Field<BoosterPhaseSetting, 1>; 3] = [
+    Field {
+        name: "soft_start_period",
+        mask: [0o300],
+        copy: |a, b| a.soft_start_period = b.soft_start_period,
+    },
+    Field {
+        name: "driving_strength",
+        mask: [0o070],
+        copy: |a, b| a.driving_strength = b.driving_strength,
+    },
+    Field {
+        name: "minimum_off_time",
+        mask: [0o007],
+        copy: |a, b| a.minimum_off_time = b.minimum_off_time,
+    },
+];
+
+fn booster_soft_start() -> impl Strategy<Value = BoosterSoftStart> {
+    (
+        booster_phase_setting(),
+        booster_phase_setting(),
+        booster_phase_setting(),
+    )
+        .prop_map(|(phase_a, phase_b, phase_c)| BoosterSoftStart {
+            phase_a,
+            phase_b,
+            phase_c,
+        })
+}
+
+const BOOSTER_SOFT_START_FIELDS: [Field<BoosterSoftStart, 3>; 3] = [
+    Field {
+        name: "phase_a",
+        mask: [0xff, 0, 0],
+        copy: |a, b| a.phase_a = b.phase_a,
+    },
+    Field {
+        name: "phase_b",
+        mask: [0, 0xff, 0],
+        copy: |a, b| a.phase_b = b.phase_b,
+    },
+    Field {
+        name: "phase_c",
+        mask: [0, 0, 0xff],
+        copy: |a, b| a.phase_c = b.phase_c,
+    },
+];
+
+/// Voltages are numbered from 2.4V in steps of 0.2V, up to 11V.
+fn internal_voltage() -> impl Strategy<Value = InternalVoltage> {
+    (0u8..=0b101011).prop_map(|code| InternalVoltage::try_from(code).unwrap())
+}
+
+fn power_setting() -> impl Strategy<Value = PowerSetting> {
+    use PowerSelection::*;
+    use VghVglVoltage::*;
+    (
+        select(vec![External, Internal]),
+        select(vec![External, Internal]),
+        select(vec![VcomVoltage::Vdh, VcomVoltage::Vgh]),
+        select(vec![V16, V15, V14, V13]),
+        internal_voltage(),
+        internal_voltage(),
+        internal_voltage(),
+    )
+        .prop_map(
+            |(
+                source_power,
+                gate_power,
+                vcom_voltage,
+                vgh_vgl_voltage,
+                internal_vdh_voltage,
+                internal_vdl_voltage,
+                internal_vdhr_voltage,
+            )| PowerSetting {
+                source_power,
+                gate_power,
+                vcom_voltage,
+                vgh_vgl_voltage,
+                internal_vdh_voltage,
+                internal_vdl_voltage,
+                internal_vdhr_voltage,
+            },
+        )
+}
+
+const POWER_SETTING_FIELDS: [Field<PowerSetting, 5>; 7] = [
+    Field {
+        name: "source_power",
+        mask: [0b10, 0, 0, 0, 0],
+        copy: |a, b| a.source_power = b.source_power,
+    },
+    Field {
+        name: "gate_power",
+        mask: [0b01, 0, 0, 0, 0],
+        copy: |a, b| a.gate_power = b.gate_power,
+    },
+    Field {
+        name: "vcom_voltage",
+        mask: [0, 0b100, 0, 0, 0],
+        copy: |a, b| a.vcom_voltage = b.vcom_voltage,
+    },
+    Field {
+        name: "vgh_vgl_voltage",
+        mask: [0, 0b011, 0, 0, 0],
+        copy: |a, b| a.vgh_vgl_voltage = b.vgh_vgl_voltage,
+    },
+    Field {
+        name: "internal_vdh_voltage",
+        mask: [0, 0, 0x3f, 0, 0],
+        copy: |a, b| a.internal_vdh_voltage = b.internal_vdh_voltage,
+    },
+    Field {
+        name: "internal_vdl_voltage",
+        mask: [0, 0, 0, 0x3f, 0],
+        copy: |a, b| a.internal_vdl_voltage = b.internal_vdl_voltage,
+    },
+    Field {
+        name: "internal_vdhr_voltage",
+        mask: [0, 0, 0, 0, 0x3f],
+        copy: |a, b| a.internal_vdhr_voltage = b.internal_vdhr_voltage,
+    },
+];
+
+fn power_off_sequence() -> impl Strategy<Value = PowerOffSequence> {
+    use PowerOffSequence::*;
+    select(vec![Frame1, Frame2, Frame3, Frame4])
+}
+
+/// The multiplier and divider of a PLL setting, in its two octal digits.
+fn pll_digits(code: u8) -> (u8, u8) {
+    (code >> 3 & 0o7, code & 0o7)
+}
+
+/// The frame rate a PLL setting gives, to the nearest hertz: 200/7 Hz times the multiplier over
+/// the divider.
+fn pll_hz(code: u8) -> u32 {
+    let (m, n) = pll_digits(code);
+    (u32::from(m) * 400 + u32::from(n) * 7) / (u32::from(n) * 14)
+}
+
+fn pll_clock_frequency() -> impl Strategy<Value = PllClockFrequency> {
+    (1u8..=7, 1u8..=7).prop_map(|(m, n)| PllClockFrequency::try_from([m << 3 | n]).unwrap())
+}
+
+fn resolution_setting() -> impl Strategy<Value = ResolutionSetting> {
+    (0u8..32, 0u16..512).prop_map(|(bank, vertical)| ResolutionSetting {
+        horizontal: bank << 3,
+        vertical,
+    })
+}
+
+const RESOLUTION_SETTING_FIELDS: [Field<ResolutionSetting, 3>; 2] = [
+    Field {
+        name: "horizontal",
+        mask: [0xf8, 0, 0],
+        copy: |a, b| a.horizontal = b.horizontal,
+    },
+    Field {
+        name: "vertical",
+        mask: [0, 0x01, 0xff],
+        copy: |a, b| a.vertical = b.vertical,
+    },
+];
+
+/// Any window the registers can hold, whether or not it fits the panel.
+fn partial_window() -> impl Strategy<Value = PartialWindow> {
+    (0u8..32, 0u8..32, 0u16..512, 0u16..512, any::<bool>()).prop_map(
+        |(
+            horizontal_start_channel_bank,
+            horizontal_end_channel_bank,
+            vertical_start_line,
+            vertical_end_line,
+            partial_scan,
+        )| PartialWindow {
+            horizontal_start_channel_bank,
+            horizontal_end_channel_bank,
+            vertical_start_line,
+            vertical_end_line,
+            partial_scan,
+        },
+    )
+}
+
+const PARTIAL_WINDOW_FIELDS: [Field<PartialWindow, 7>; 5] = [
+    Field {
+        name: "horizontal_start_channel_bank",
+        mask: [0xf8, 0, 0, 0, 0, 0, 0],
+        copy: |a, b| a.horizontal_start_channel_bank = b.horizontal_start_channel_bank,
+    },
+    Field {
+        name: "horizontal_end_channel_bank",
+        mask: [0, 0xf8, 0, 0, 0, 0, 0],
+        copy: |a, b| a.horizontal_end_channel_bank = b.horizontal_end_channel_bank,
+    },
+    Field {
+        name: "vertical_start_line",
+        mask: [0, 0, 0x01, 0xff, 0, 0, 0],
+        copy: |a, b| a.vertical_start_line = b.vertical_start_line,
+    },
+    Field {
+        name: "vertical_end_line",
+        mask: [0, 0, 0, 0, 0x01, 0xff, 0],
+        copy: |a, b| a.vertical_end_line = b.vertical_end_line,
+    },
+    Field {
+        name: "partial_scan",
+        mask: [0, 0, 0, 0, 0, 0, 0x01],
+        copy: |a, b| a.partial_scan = b.partial_scan,
+    },
+];
+
+/// The horizontal end bank is always sent with its low bits set.
+const PARTIAL_WINDOW_FIXED: [u8; 7] = [0, 0x07, 0, 0, 0, 0, 0];
+
+fn lut_setting() -> impl Strategy<Value = LutSetting> {
+    use LevelSelection::*;
+    let level = || select(vec![Gnd, Vdh, Vdl, Vdhr]);
+    (
+        (level(), level(), level(), level()),
+        any::<[u8; 4]>(),
+        any::<u8>(),
+    )
+        .prop_map(|(levels, frames, times_to_repeat)| LutSetting {
+            level_select_1: levels.0,
+            level_select_2: levels.1,
+            level_select_3: levels.2,
+            level_select_4: levels.3,
+            number_of_frames_1: frames[0],
+            number_of_frames_2: frames[1],
+            number_of_frames_3: frames[2],
+            number_of_frames_4: frames[3],
+            times_to_repeat,
+        })
+}
+
+const LUT_SETTING_FIELDS: [Field<LutSetting, 6>; 9] = [
+    Field {
+        name: "level_select_1",
+        mask: [0b11_00_00_00, 0, 0, 0, 0, 0],
+        copy: |a, b| a.level_select_1 = b.level_select_1,
+    },
+    Field {
+        name: "level_select_2",
+        mask: [0b00_11_00_00, 0, 0, 0, 0, 0],
+        copy: |a, b| a.level_select_2 = b.level_select_2,
+    },
+    Field {
+        name: "level_select_3",
+        mask: [0b00_00_11_00, 0, 0, 0, 0, 0],
+        copy: |a, b| a.level_select_3 = b.level_select_3,
+    },
+    Field {
+        name: "level_select_4",
+        mask: [0b00_00_00_11, 0, 0, 0, 0, 0],
+        copy: |a, b| a.level_select_4 = b.level_select_4,
+    },
+    Field {
+        name: "number_of_frames_1",
+        mask: [0, 0xff, 0, 0, 0, 0],
+        copy: |a, b| a.number_of_frames_1 = b.number_of_frames_1,
+    },
+    Field {
+        name: "number_of_frames_2",
+        mask: [0, 0, 0xff, 0, 0, 0],
+        copy: |a, b| a.number_of_frames_2 = b.number_of_frames_2,
+    },
+    Field {
+        name: "number_of_frames_3",
+        mask: [0, 0, 0, 0xff, 0, 0],
+        copy: |a, b| a.number_of_frames_3 = b.number_of_frames_3,
+    },
+    Field {
+        name: "number_of_frames_4",
+        mask: [0, 0, 0, 0, 0xff, 0],
+        copy: |a, b| a.number_of_frames_4 = b.number_of_frames_4,
+    },
+    Field {
+        name: "times_to_repeat",
+        mask: [0, 0, 0, 0, 0, 0xff],
+        copy: |a, b| a.times_to_repeat = b.times_to_repeat,
+    },
+];
+
+fn lut_setting_group() -> impl Strategy<Value = LutSettingGroup> {
+    (
+        lut_setting(),
+        lut_setting(),
+        lut_setting(),
+        lut_setting(),
+        lut_setting(),
+        lut_setting(),
+        lut_setting(),
+    )
+        .prop_map(|(s0, s1, s2, s3, s4, s5, s6)| LutSettingGroup([s0, s1, s2, s3, s4, s5, s6]))
+}
+
+proptest! {
+    #[test]
+    fn panel_setting_round_trips(setting in panel_setting(), byte in any::<u8>()) {
+        prop_assert_eq!(setting, PanelSetting::from(<[u8; 1]>::from(setting)));
+        let bytes = and([byte], mask(&PANEL_SETTING_FIELDS));
+        prop_assert_eq!(bytes, <[u8; 1]>::from(PanelSetting::from([byte])));
+    }
+
+    #[test]
+    fn panel_setting_fields(a in panel_setting(), b in panel_setting()) {
+        check_fields(&PANEL_SETTING_FIELDS, |setting| (*setting).into(), &a, &b)?;
+    }
+
+    #[test]
+    fn booster_phase_setting_round_trips(setting in booster_phase_setting(), byte in any::<u8>()) {
+        prop_assert_eq!(setting, BoosterPhaseSetting::from(u8::from(setting)));
+        let [bits] = and([byte], mask(&BOOSTER_PHASE_FIELDS));
+        prop_assert_eq!(bits, u8::from(BoosterPhaseSetting::from(byte)));
+    }
+
+    #[test]
+    fn booster_phase_setting_fields(a in booster_phase_setting(), b in booster_phase_setting()) {
+        check_fields(&BOOSTER_PHASE_FIELDS, |setting| [u8::from(*setting)], &a, &b)?;
+    }
+
+    #[test]
+    fn booster_soft_start_round_trips(setting in booster_soft_start(), bytes in any::<[u8; 3]>()) {
+        prop_assert_eq!(setting, BoosterSoftStart::from(<[u8; 3]>::from(setting)));
+        prop_assert_eq!(bytes, <[u8; 3]>::from(BoosterSoftStart::from(bytes)));
+    }
+
+    #[test]
+    fn booster_soft_start_fields(a in booster_soft_start(), b in booster_soft_start()) {
+        check_fields(&BOOSTER_SOFT_START_FIELDS, |setting| (*setting).into(), &a, &b)?;
+    }
+
+    #[test]
+    fn power_setting_round_trips(setting in power_setting(), bytes in any::<[u8; 5]>()) {
+        prop_assert_eq!(Ok(setting), PowerSetting::try_from(<[u8; 5]>::from(setting)));
+        let bytes = and(bytes, mask(&POWER_SETTING_FIELDS));
+        match PowerSetting::try_from(bytes) {
+            Ok(setting) => prop_assert_eq!(bytes, <[u8; 5]>::from(setting)),
+            Err(code) => {
+                prop_assert!(code > 0b101011);
+                prop_assert_eq!(Some(&code), bytes[2..].iter().find(|&&code| code > 0b101011));
+            }
+        }
+    }
+
+    #[test]
+    fn power_setting_fields(a in power_setting(), b in power_setting()) {
+        check_fields(&POWER_SETTING_FIELDS, |setting| (*setting).into(), &a, &b)?;
+    }
+
+    #[test]
+    fn power_off_sequence_round_trips(sequence in power_off_sequence(), byte in any::<u8>()) {
+        prop_assert_eq!(sequence, PowerOffSequence::from(<[u8; 1]>::from(sequence)));
+        let bits = [byte & 0b11_0000];
+        prop_assert_eq!(bits, <[u8; 1]>::from(PowerOffSequence::from([byte])));
+    }
+
+    #[test]
+    fn pll_clock_frequency_round_trips(frequency in pll_clock_frequency(), byte in any::<u8>()) {
+        prop_assert_eq!(Ok(frequency), PllClockFrequency::try_from(<[u8; 1]>::from(frequency)));
+
+        // Codes that give the same frequency decode to the same setting.
+        let (m, n) = pll_digits(byte);
+        match PllClockFrequency::try_from([byte]) {
+            Ok(frequency) => {
+                let [code] = <[u8; 1]>::from(frequency);
+                prop_assert!(byte < 0o100 && m > 0 && n > 0);
+                prop_assert_eq!(pll_hz(byte), pll_hz(code));
+            }
+            Err(code) => {
+                prop_assert_eq!(byte, code);
+                prop_assert!(byte >= 0o100 || m == 0 || n == 0);
+            }
+        }
+    }
+
+    #[test]
+    fn resolution_setting_round_trips(setting in resolution_setting(), bytes in any::<[u8; 3]>()) {
+        prop_assert_eq!(setting, ResolutionSetting::from(<[u8; 3]>::from(setting)));
+        let bytes = and(bytes, mask(&RESOLUTION_SETTING_FIELDS));
+        prop_assert_eq!(bytes, <[u8; 3]>::from(ResolutionSetting::from(bytes)));
+    }
+
+    #[test]
+    fn resolution_setting_fields(a in resolution_setting(), b in resolution_setting()) {
+        check_fields(&RESOLUTION_SETTING_FIELDS, |setting| (*setting).into(), &a, &b)?;
+    }
+
+    #[test]
+    fn partial_window_round_trips(window in partial_window(), bytes in any::<[u8; 7]>()) {
+        prop_assert_eq!(window, PartialWindow::from(<[u8; 7]>::from(window)));
+        let masked = and(bytes, mask(&PARTIAL_WINDOW_FIELDS));
+        let expected = zip(masked, PARTIAL_WINDOW_FIXED, |a, b| a | b);
+        prop_assert_eq!(expected, <[u8; 7]>::from(PartialWindow::from(bytes)));
+    }
+
+    #[test]
+    fn partial_window_fields(a in partial_window(), b in partial_window()) {
+        check_fields(&PARTIAL_WINDOW_FIELDS, |window| (*window).into(), &a, &b)?;
+    }
+
+    #[test]
+    fn partial_window_new_round_trips(window in partial_window()) {
+        if let Ok(checked) = PartialWindow::new(
+            window.horizontal_start_channel_bank,
+            window.horizontal_end_channel_bank,
+            window.vertical_start_line,
+            window.vertical_end_line,
+            window.partial_scan,
+            256,
+            512,
+        ) {
+            prop_assert_eq!(window, checked);
+            prop_assert_eq!(checked, PartialWindow::from(<[u8; 7]>::from(checked)));
+        }
+    }
+
+    #[test]
+    fn lut_setting_round_trips(setting in lut_setting(), bytes in any::<[u8; 6]>()) {
+        prop_assert_eq!(&setting, &LutSetting::from(<[u8; 6]>::from(&setting)));
+        prop_assert_eq!(bytes, <[u8; 6]>::from(&LutSetting::from(bytes)));
+    }
+
+    #[test]
+    fn lut_setting_fields(a in lut_setting(), b in lut_setting()) {
+        check_fields(&LUT_SETTING_FIELDS, |setting| setting.into(), &a, &b)?;
+    }
+
+    #[test]
+    fn lut_setting_group_round_trips(group in lut_setting_group(), bytes in any::<[u8; 42]>()) {
+        let encoded = <[u8; 42]>::from(group.clone());
+        prop_assert_eq!(&group, &LutSettingGroup::from(encoded));
+        prop_assert_eq!(bytes.to_vec(), <[u8; 42]>::from(LutSettingGroup::from(bytes)).to_vec());
+
+        // The VCOM table is the same with two more bytes of zeros.
+        let vcom = <[u8; 44]>::from(group);
+        prop_assert_eq!(&encoded[..], &vcom[..42]);
+        prop_assert_eq!(&[0, 0], &vcom[42..]);
+    }
+}
+
+#[test]
+fn internal_voltage_codes() {
+    for code in 0..=u8::MAX {
+        match InternalVoltage::try_from(code) {
+            Ok(voltage) => assert_eq!(code, voltage as u8),
+            Err(rejected) => {
+                assert_eq!(code, rejected);
+                assert!(code > 0b101011);
+            }
+        }
+    }
+}
+
+#[test]
+fn pll_clock_frequency_codes() {
+    for byte in 0..0o100 {
+        if let Ok(frequency) = PllClockFrequency::try_from([byte]) {
+            let [code] = <[u8; 1]>::from(frequency);
+            assert_eq!(
+                pll_hz(byte),
+                pll_hz(code),
+                "{:o} decodes to {:?}",
+                byte,
+                frequency
+            );
+        }
+    }
+}